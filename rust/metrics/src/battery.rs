@@ -0,0 +1,177 @@
+use crate::{Sample, sysfs::Sysfs};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, uniffi::Enum, serde::Serialize)]
+pub enum ChargingState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct Battery {
+    /// Directory name, e.g. `battery`
+    pub name: String,
+    /// Percent
+    pub level: Option<f64>,
+    pub state: ChargingState,
+    /// Volts
+    pub voltage: Option<f64>,
+    /// Amps, positive when discharging
+    pub current: Option<f64>,
+    /// Watts, positive when discharging
+    pub power: Option<f64>,
+}
+
+/// Reads the first battery under `power_supply`
+///
+/// `sysfs_root` defaults to `/sys`. `None` when there is no battery or sysfs.
+#[uniffi::export]
+pub fn get_battery(sysfs_root: Option<String>) -> Option<Battery> {
+    read_battery(&Sysfs::new(sysfs_root))
+}
+
+/// Joules drawn from the battery over `samples`, integrating their power
+///
+/// `None` if fewer than two samples have a power reading.
+#[uniffi::export]
+pub fn energy_consumed(samples: Vec<Sample>) -> Option<f64> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .filter_map(|s| Some((s.timestamp, s.battery.as_ref()?.power?)))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+
+    // trapezoidal rule, timestamps are in milliseconds
+    Some(
+        points
+            .windows(2)
+            .map(|w| (w[1].0 - w[0].0) / 1000.0 * (w[0].1 + w[1].1) / 2.0)
+            .sum(),
+    )
+}
+
+pub(crate) fn read_battery(sysfs: &Sysfs) -> Option<Battery> {
+    let (name, path) = sysfs
+        .entries("class/power_supply", "")
+        .into_iter()
+        .find(|(_, path)| sysfs.read(path.join("type")).as_deref() == Some("Battery"))?;
+
+    let state = match sysfs.read(path.join("status")).as_deref() {
+        Some("Charging") => ChargingState::Charging,
+        Some("Discharging") => ChargingState::Discharging,
+        Some("Full") => ChargingState::Full,
+        Some("Not charging") => ChargingState::NotCharging,
+        _ => ChargingState::Unknown,
+    };
+
+    // drivers disagree on the sign of current_now, so derive it from the state
+    let sign = if state == ChargingState::Charging {
+        -1.0
+    } else {
+        1.0
+    };
+    let micro = |file: &str| sysfs.read_i64(path.join(file)).map(|v| v as f64 / 1e6);
+
+    let voltage = micro("voltage_now");
+    let current = micro("current_now").map(|a| sign * a.abs());
+    let power = micro("power_now")
+        .map(|w| sign * w.abs())
+        .or_else(|| Some(voltage? * current?));
+
+    Some(Battery {
+        name,
+        level: sysfs.read_i64(path.join("capacity")).map(|v| v as f64),
+        state,
+        voltage,
+        current,
+        power,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FakeSysfs;
+
+    fn approx(a: Option<f64>, b: f64) -> bool {
+        a.is_some_and(|a| (a - b).abs() < 1e-9)
+    }
+
+    fn sample(timestamp: f64, power: Option<f64>) -> Sample {
+        Sample {
+            timestamp,
+            cpu: None,
+            cores: None,
+            memory: None,
+            process: None,
+            battery: Some(Battery {
+                name: "battery".to_string(),
+                level: None,
+                state: ChargingState::Discharging,
+                voltage: None,
+                current: None,
+                power,
+            }),
+            pressure: None,
+        }
+    }
+
+    #[test]
+    fn reads_first_battery_supply() {
+        let fake = FakeSysfs::new();
+        fake.write("class/power_supply/ac/type", "Mains")
+            .write("class/power_supply/battery/type", "Battery")
+            .write("class/power_supply/battery/status", "Discharging")
+            .write("class/power_supply/battery/capacity", "87")
+            .write("class/power_supply/battery/voltage_now", "3800000")
+            .write("class/power_supply/battery/current_now", "-500000")
+            .write("class/power_supply/battery/power_now", "1900000");
+
+        let b = get_battery(fake.root()).unwrap();
+        assert_eq!(b.name, "battery");
+        assert_eq!(b.state, ChargingState::Discharging);
+        assert_eq!(b.level, Some(87.0));
+        assert!(approx(b.voltage, 3.8));
+        assert!(approx(b.current, 0.5));
+        assert!(approx(b.power, 1.9));
+    }
+
+    #[test]
+    fn derives_power_and_sign_when_charging() {
+        let fake = FakeSysfs::new();
+        fake.write("class/power_supply/bms/type", "Battery")
+            .write("class/power_supply/bms/status", "Charging")
+            .write("class/power_supply/bms/voltage_now", "4000000")
+            .write("class/power_supply/bms/current_now", "250000");
+
+        let b = get_battery(fake.root()).unwrap();
+        assert_eq!(b.state, ChargingState::Charging);
+        assert_eq!(b.level, None);
+        assert!(approx(b.current, -0.25));
+        assert!(approx(b.power, -1.0));
+    }
+
+    #[test]
+    fn none_without_battery() {
+        let fake = FakeSysfs::new();
+        assert_eq!(get_battery(fake.root()), None);
+        fake.write("class/power_supply/usb/type", "USB");
+        assert_eq!(get_battery(fake.root()), None);
+    }
+
+    #[test]
+    fn integrates_energy() {
+        let samples = vec![
+            sample(0.0, Some(2.0)),
+            sample(500.0, None),
+            sample(1000.0, Some(4.0)),
+            sample(3000.0, Some(4.0)),
+        ];
+        assert!(approx(energy_consumed(samples), 3.0 + 8.0));
+        assert_eq!(energy_consumed(vec![sample(0.0, Some(1.0))]), None);
+    }
+}
@@ -0,0 +1,169 @@
+use std::{sync::Arc, time::Instant};
+
+use crate::{MetricsError, get_thermal_status, stats};
+
+/// Code to benchmark, usually implemented in JS
+#[uniffi::export(with_foreign)]
+pub trait BenchTarget: Send + Sync {
+    fn run(&self) -> Result<(), MetricsError>;
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct BenchConfig {
+    pub name: String,
+    /// Untimed runs before measuring, to let the JIT and caches settle
+    #[uniffi(default = 10)]
+    pub warmup_iterations: u32,
+    #[uniffi(default = 100)]
+    pub iterations: u32,
+}
+
+/// Timings of a benchmark run, in milliseconds
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize, serde::Deserialize)]
+pub struct BenchResult {
+    pub name: String,
+    pub iterations: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    pub stddev: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub ops_per_sec: f64,
+    /// Duration of every timed iteration, in run order
+    pub samples: Vec<f64>,
+    /// Whether the CPU was throttled at the start or end of the run
+    #[serde(default)]
+    pub throttled: bool,
+}
+
+/// Runs `target` for the configured warmup and timed iterations
+///
+/// Stops at the first error thrown by `target`.
+/// Throttling is read from the default sysfs root.
+#[uniffi::export]
+pub fn run_benchmark(
+    target: Arc<dyn BenchTarget>,
+    config: BenchConfig,
+) -> Result<BenchResult, MetricsError> {
+    if config.iterations == 0 {
+        return Err(MetricsError::InvalidArgument {
+            reason: "iterations must be at least 1".to_string(),
+        });
+    }
+
+    for _ in 0..config.warmup_iterations {
+        target.run()?;
+    }
+
+    let throttled_before = get_thermal_status(None).throttled;
+    let mut samples = Vec::with_capacity(config.iterations as usize);
+    for _ in 0..config.iterations {
+        let start = Instant::now();
+        target.run()?;
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    let throttled = throttled_before || get_thermal_status(None).throttled;
+
+    let sorted = stats::sorted(&samples);
+    let mean = stats::mean(&samples);
+    Ok(BenchResult {
+        name: config.name,
+        iterations: config.iterations,
+        mean,
+        median: stats::percentile(&sorted, 50.0),
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        stddev: stats::stddev(&samples),
+        p95: stats::percentile(&sorted, 95.0),
+        p99: stats::percentile(&sorted, 99.0),
+        ops_per_sec: if mean > 0.0 {
+            1000.0 / mean
+        } else {
+            f64::INFINITY
+        },
+        samples,
+        throttled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    /// Counts calls and fails on call `fail_on`, if set
+    struct Counter {
+        calls: AtomicU32,
+        fail_on: Option<u32>,
+    }
+
+    impl Counter {
+        fn new(fail_on: Option<u32>) -> Arc<Self> {
+            Arc::new(Counter {
+                calls: AtomicU32::new(0),
+                fail_on,
+            })
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    impl BenchTarget for Counter {
+        fn run(&self) -> Result<(), MetricsError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if Some(call) == self.fail_on {
+                return Err(MetricsError::InvalidArgument {
+                    reason: format!("call {}", call),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    fn config(warmup_iterations: u32, iterations: u32) -> BenchConfig {
+        BenchConfig {
+            name: "counter".to_string(),
+            warmup_iterations,
+            iterations,
+        }
+    }
+
+    #[test]
+    fn runs_warmup_and_timed_iterations() {
+        let target = Counter::new(None);
+        let result = run_benchmark(target.clone(), config(3, 7)).unwrap();
+        assert_eq!(target.calls(), 10);
+        assert_eq!(result.name, "counter");
+        assert_eq!(result.iterations, 7);
+        assert_eq!(result.samples.len(), 7);
+        assert!(result.min <= result.median && result.median <= result.max);
+        assert!(result.ops_per_sec > 0.0);
+    }
+
+    #[test]
+    fn rejects_zero_iterations() {
+        let target = Counter::new(None);
+        let err = run_benchmark(target.clone(), config(3, 0)).unwrap_err();
+        assert!(matches!(err, MetricsError::InvalidArgument { .. }));
+        assert_eq!(target.calls(), 0);
+    }
+
+    #[test]
+    fn stops_at_first_error() {
+        // once during warmup, once while timing
+        for fail_on in [2, 6] {
+            let target = Counter::new(Some(fail_on));
+            let err = run_benchmark(target.clone(), config(3, 7)).unwrap_err();
+            assert!(
+                matches!(&err, MetricsError::InvalidArgument { reason } if *reason == format!("call {}", fail_on))
+            );
+            assert_eq!(target.calls(), fail_on);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+};
+
+/// Runs `f` on a new thread, resolving once it returns
+///
+/// Lets blocking measurements be exported as uniffi futures
+/// without pulling in an async runtime. A panic in `f` is resumed
+/// when the future is polled, which uniffi turns into a rejection.
+pub(crate) fn spawn<T, F>(f: F) -> Blocking<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(State {
+        result: None,
+        waker: None,
+    }));
+
+    let shared = state.clone();
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let mut s = shared.lock().unwrap();
+        s.result = Some(result);
+        if let Some(waker) = s.waker.take() {
+            waker.wake();
+        }
+    });
+
+    Blocking { state }
+}
+
+#[derive(Debug)]
+struct State<T> {
+    result: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Blocking<T> {
+    state: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Future for Blocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut s = self.state.lock().unwrap();
+        match s.result.take() {
+            Some(Ok(result)) => Poll::Ready(result),
+            Some(Err(payload)) => {
+                drop(s);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                s.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Drives `fut` to completion on the current thread
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(fut: F) -> F::Output {
+    struct Unpark(thread::Thread);
+
+    impl std::task::Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            Poll::Ready(v) => return v,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_with_result() {
+        assert_eq!(block_on(spawn(|| 6 * 7)), 42);
+    }
+
+    #[test]
+    fn resumes_panics_on_poll() {
+        let polled = thread::spawn(|| block_on(spawn(|| -> u32 { panic!("boom") }))).join();
+        let payload = polled.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    }
+}
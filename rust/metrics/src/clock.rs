@@ -0,0 +1,24 @@
+use std::{
+    sync::OnceLock,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+static EPOCH: OnceLock<(Instant, SystemTime)> = OnceLock::new();
+
+fn epoch() -> &'static (Instant, SystemTime) {
+    EPOCH.get_or_init(|| (Instant::now(), SystemTime::now()))
+}
+
+/// Milliseconds since the crate's clock was first read
+///
+/// Monotonic, so every timestamp in this crate can be compared with another.
+pub(crate) fn now_ms() -> f64 {
+    epoch().0.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Converts a timestamp of [`now_ms`] to wall-clock nanoseconds since the Unix epoch
+#[cfg_attr(not(feature = "otlp"), allow(dead_code))]
+pub(crate) fn to_unix_nanos(ms: f64) -> u64 {
+    let origin = epoch().1.duration_since(UNIX_EPOCH).unwrap_or_default();
+    origin.as_nanos() as u64 + (ms * 1_000_000.0) as u64
+}
@@ -0,0 +1,257 @@
+use crate::{MetricsError, stats};
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct CompareConfig {
+    /// Relative changes smaller than this are noise, e.g. 0.02 for 2%
+    #[uniffi(default = 0.02)]
+    pub noise_threshold: f64,
+    /// p-value below which a difference is significant
+    #[uniffi(default = 0.05)]
+    pub significance: f64,
+    /// Whether smaller values are better, as with timings
+    #[uniffi(default = true)]
+    pub lower_is_better: bool,
+}
+
+impl Default for CompareConfig {
+    fn default() -> Self {
+        Self {
+            noise_threshold: 0.02,
+            significance: 0.05,
+            lower_is_better: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    NoChange,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Comparison {
+    pub baseline_mean: f64,
+    pub candidate_mean: f64,
+    /// `(candidate - baseline) / baseline`
+    pub relative_change: f64,
+    pub mann_whitney_u: f64,
+    /// Two-sided, normal approximation with tie correction
+    pub mann_whitney_p: f64,
+    pub welch_t: f64,
+    /// Two-sided
+    pub welch_p: f64,
+    pub verdict: Verdict,
+}
+
+/// Compares two sample sets, e.g. the `samples` of two benchmark runs
+///
+/// The verdict uses the Mann-Whitney p-value since timings are rarely normal.
+#[uniffi::export]
+pub fn compare(
+    baseline: Vec<f64>,
+    candidate: Vec<f64>,
+    config: CompareConfig,
+) -> Result<Comparison, MetricsError> {
+    if baseline.len() < 2 || candidate.len() < 2 {
+        return Err(MetricsError::InvalidArgument {
+            reason: "need at least 2 samples on each side to compare".to_string(),
+        });
+    }
+
+    if baseline.iter().chain(&candidate).any(|x| !x.is_finite()) {
+        return Err(MetricsError::InvalidArgument {
+            reason: "samples must be finite".to_string(),
+        });
+    }
+
+    let baseline_mean = stats::mean(&baseline);
+    if baseline_mean == 0.0 {
+        return Err(MetricsError::InvalidArgument {
+            reason: "relative change is undefined for a baseline mean of 0".to_string(),
+        });
+    }
+    let candidate_mean = stats::mean(&candidate);
+    let relative_change = (candidate_mean - baseline_mean) / baseline_mean;
+    let (mann_whitney_u, mann_whitney_p) = mann_whitney(&baseline, &candidate);
+    let (welch_t, _, welch_p) = welch(&baseline, &candidate);
+
+    let verdict = if mann_whitney_p >= config.significance
+        || relative_change.abs() < config.noise_threshold
+    {
+        Verdict::NoChange
+    } else if (relative_change < 0.0) == config.lower_is_better {
+        Verdict::Improved
+    } else {
+        Verdict::Regressed
+    };
+
+    Ok(Comparison {
+        baseline_mean,
+        candidate_mean,
+        relative_change,
+        mann_whitney_u,
+        mann_whitney_p,
+        welch_t,
+        welch_p,
+        verdict,
+    })
+}
+
+/// U statistic of `a` and its two-sided p-value
+fn mann_whitney(a: &[f64], b: &[f64]) -> (f64, f64) {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    let mut pooled: Vec<(f64, bool)> = a
+        .iter()
+        .map(|x| (*x, true))
+        .chain(b.iter().map(|x| (*x, false)))
+        .collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // average ranks over ties
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j + 1 < pooled.len() && pooled[j + 1].0 == pooled[i].0 {
+            j += 1;
+        }
+
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let ties = (j - i + 1) as f64;
+        rank_sum_a += rank * pooled[i..=j].iter().filter(|(_, in_a)| *in_a).count() as f64;
+        tie_term += ties.powi(3) - ties;
+        i = j + 1;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let sigma = (n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)))).sqrt();
+    if sigma == 0.0 {
+        return (u, 1.0);
+    }
+
+    // continuity correction towards the mean
+    let z = ((u - mean).abs() - 0.5).max(0.0) / sigma;
+    (u, (2.0 * (1.0 - stats::normal_cdf(z))).min(1.0))
+}
+
+/// Welch's t statistic of `a` against `b`, its degrees of freedom
+/// and two-sided p-value
+fn welch(a: &[f64], b: &[f64]) -> (f64, f64, f64) {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let v1 = stats::stddev(a).powi(2) / n1;
+    let v2 = stats::stddev(b).powi(2) / n2;
+    let diff = stats::mean(a) - stats::mean(b);
+
+    let se = (v1 + v2).sqrt();
+    if se == 0.0 {
+        let df = n1 + n2 - 2.0;
+        return if diff == 0.0 {
+            (0.0, df, 1.0)
+        } else {
+            (diff.signum() * f64::INFINITY, df, 0.0)
+        };
+    }
+
+    let t = diff / se;
+    let df = (v1 + v2).powi(2) / (v1.powi(2) / (n1 - 1.0) + v2.powi(2) / (n2 - 1.0));
+    (t, df, stats::student_t_two_sided(t, df))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // reference values computed with mpmath at 30 digits
+    const A1: [f64; 15] = [
+        27.5, 21.0, 19.0, 23.6, 17.0, 17.9, 16.9, 20.1, 21.9, 22.6, 23.1, 19.6, 19.0, 21.7, 21.4,
+    ];
+    const A2: [f64; 15] = [
+        27.1, 22.0, 20.8, 23.4, 23.4, 23.5, 25.8, 22.0, 24.8, 20.2, 21.9, 22.1, 22.9, 20.5, 24.4,
+    ];
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn welch_matches_reference() {
+        let (t, df, p) = welch(&A1, &A2);
+        assert_close(t, -2.455_356_398_29, 1e-9);
+        assert_close(df, 24.988_529_290_2, 1e-9);
+        assert_close(p, 0.021_378_001_462_9, 1e-9);
+    }
+
+    #[test]
+    fn mann_whitney_matches_reference_with_ties() {
+        let (u, p) = mann_whitney(
+            &[1.0, 2.0, 2.0, 3.0, 4.0, 5.0],
+            &[3.0, 4.0, 5.0, 5.0, 6.0, 7.0, 8.0],
+        );
+        assert_eq!(u, 5.0);
+        assert_close(p, 0.025_359_042_166_4, 1e-6);
+    }
+
+    #[test]
+    fn identical_inputs_are_no_change() {
+        let c = compare(A1.to_vec(), A1.to_vec(), CompareConfig::default()).unwrap();
+        assert_eq!(c.verdict, Verdict::NoChange);
+        assert_eq!(c.relative_change, 0.0);
+        assert_eq!(c.welch_t, 0.0);
+        assert_eq!(c.welch_p, 1.0);
+        assert_close(c.mann_whitney_p, 1.0, 1e-6);
+    }
+
+    #[test]
+    fn large_shift_follows_direction() {
+        let slower: Vec<f64> = A1.iter().map(|x| x * 2.0).collect();
+        let lower = CompareConfig::default();
+        let higher = CompareConfig {
+            lower_is_better: false,
+            ..lower
+        };
+
+        let c = compare(A1.to_vec(), slower.clone(), lower).unwrap();
+        assert_eq!(c.verdict, Verdict::Regressed);
+        assert_close(c.relative_change, 1.0, 1e-12);
+        assert_eq!(
+            compare(A1.to_vec(), slower.clone(), higher)
+                .unwrap()
+                .verdict,
+            Verdict::Improved
+        );
+        assert_eq!(
+            compare(slower.clone(), A1.to_vec(), lower).unwrap().verdict,
+            Verdict::Improved
+        );
+        assert_eq!(
+            compare(slower, A1.to_vec(), higher).unwrap().verdict,
+            Verdict::Regressed
+        );
+    }
+
+    #[test]
+    fn small_shift_is_noise() {
+        let shifted: Vec<f64> = A1.iter().map(|x| x * 1.01).collect();
+        let c = compare(A1.to_vec(), shifted, CompareConfig::default()).unwrap();
+        assert_eq!(c.verdict, Verdict::NoChange);
+    }
+
+    #[test]
+    fn rejects_undefined_inputs() {
+        let config = CompareConfig::default();
+        assert!(compare(vec![1.0], vec![1.0, 2.0], config).is_err());
+        assert!(compare(vec![-1.0, 1.0], vec![1.0, 2.0], config).is_err());
+        assert!(compare(vec![1.0, f64::NAN], vec![1.0, 2.0], config).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::MetricsError;
+
+/// Cumulative resource counters of the current process
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct ProcessCounters {
+    /// Milliseconds on the shared monotonic clock
+    pub timestamp: f64,
+    /// Page faults served without disk IO
+    pub minor_faults: u64,
+    /// Page faults that had to read from disk
+    pub major_faults: u64,
+    /// Times the process yielded, e.g. waiting on a lock or IO
+    pub voluntary_context_switches: u64,
+    /// Times the process was preempted by the scheduler
+    pub involuntary_context_switches: u64,
+    /// Current number of open file descriptors
+    pub open_fds: u32,
+    /// Current number of threads
+    pub threads: u32,
+}
+
+/// Change between two [`ProcessCounters`] snapshots
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct ProcessCountersDelta {
+    /// Milliseconds between the snapshots
+    pub elapsed: f64,
+    pub minor_faults: u64,
+    pub major_faults: u64,
+    pub voluntary_context_switches: u64,
+    pub involuntary_context_switches: u64,
+    pub open_fds: i64,
+    pub threads: i64,
+}
+
+/// Snapshot of the current process' counters
+///
+/// Only available where `/proc` is (Linux & Android).
+#[uniffi::export]
+pub fn get_process_counters() -> Result<ProcessCounters, MetricsError> {
+    read_counters()
+}
+
+#[uniffi::export]
+pub fn process_counters_delta(
+    start: ProcessCounters,
+    end: ProcessCounters,
+) -> ProcessCountersDelta {
+    ProcessCountersDelta {
+        elapsed: end.timestamp - start.timestamp,
+        minor_faults: end.minor_faults.saturating_sub(start.minor_faults),
+        major_faults: end.major_faults.saturating_sub(start.major_faults),
+        voluntary_context_switches: end
+            .voluntary_context_switches
+            .saturating_sub(start.voluntary_context_switches),
+        involuntary_context_switches: end
+            .involuntary_context_switches
+            .saturating_sub(start.involuntary_context_switches),
+        open_fds: end.open_fds as i64 - start.open_fds as i64,
+        threads: end.threads as i64 - start.threads as i64,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn read_counters() -> Result<ProcessCounters, MetricsError> {
+    use crate::{clock, procfs};
+
+    let timestamp = clock::now_ms();
+    let stat = procfs::read_stat("/proc/self/stat")?;
+    let status = procfs::read_status("/proc/self/status")?;
+    // the listing itself holds one descriptor open
+    let open_fds = procfs::count_fds("/proc/self/fd")?.saturating_sub(1);
+
+    Ok(ProcessCounters {
+        timestamp,
+        minor_faults: stat.minflt,
+        major_faults: stat.majflt,
+        voluntary_context_switches: status.voluntary_ctxt_switches,
+        involuntary_context_switches: status.nonvoluntary_ctxt_switches,
+        open_fds,
+        threads: stat.num_threads as u32,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn read_counters() -> Result<ProcessCounters, MetricsError> {
+    Err(MetricsError::Io {
+        reason: "process counters need /proc".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(timestamp: f64, faults: u64, fds: u32, threads: u32) -> ProcessCounters {
+        ProcessCounters {
+            timestamp,
+            minor_faults: faults,
+            major_faults: faults / 10,
+            voluntary_context_switches: faults * 2,
+            involuntary_context_switches: faults * 3,
+            open_fds: fds,
+            threads,
+        }
+    }
+
+    #[test]
+    fn deltas_between_snapshots() {
+        let d = process_counters_delta(counters(100.0, 50, 10, 4), counters(350.0, 120, 12, 3));
+        assert_eq!(
+            d,
+            ProcessCountersDelta {
+                elapsed: 250.0,
+                minor_faults: 70,
+                major_faults: 7,
+                voluntary_context_switches: 140,
+                involuntary_context_switches: 210,
+                open_fds: 2,
+                threads: -1,
+            }
+        );
+    }
+
+    #[test]
+    fn cumulative_deltas_saturate() {
+        let d = process_counters_delta(counters(0.0, 120, 12, 3), counters(1.0, 50, 10, 4));
+        assert_eq!(d.minor_faults, 0);
+        assert_eq!(d.involuntary_context_switches, 0);
+        assert_eq!((d.open_fds, d.threads), (-2, 1));
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn counts_opened_files() {
+        // other tests open and close descriptors concurrently, so retry
+        // until a pair of snapshots sees only this one
+        let opened = (0..50).any(|_| {
+            let start = get_process_counters().unwrap();
+            let file = std::fs::File::open("/proc/self/stat").unwrap();
+            let end = get_process_counters().unwrap();
+            drop(file);
+            process_counters_delta(start, end).open_fds == 1
+        });
+        assert!(opened);
+
+        let c = get_process_counters().unwrap();
+        assert!(c.threads >= 1 && c.minor_faults > 0);
+    }
+}
@@ -0,0 +1,48 @@
+use std::thread;
+
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+
+use crate::blocking;
+
+/// Usage and frequency of a single logical core
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct CpuCore {
+    pub index: u32,
+    /// Usage in percent
+    pub usage: f32,
+    /// Current frequency in MHz
+    pub frequency: u64,
+    pub vendor: String,
+    pub brand: String,
+}
+
+/// Blocks for `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL` to measure the delta.
+#[uniffi::export]
+pub fn get_cpu_cores() -> Vec<CpuCore> {
+    let mut sys =
+        System::new_with_specifics(RefreshKind::nothing().with_cpu(CpuRefreshKind::everything()));
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    read_cores(&mut sys)
+}
+
+/// Non-blocking [`get_cpu_cores`], measured on a background thread
+#[uniffi::export]
+pub async fn get_cpu_cores_async() -> Vec<CpuCore> {
+    blocking::spawn(get_cpu_cores).await
+}
+
+/// Refreshes only CPU usage and frequency on `sys`
+pub(crate) fn read_cores(sys: &mut System) -> Vec<CpuCore> {
+    sys.refresh_cpu_specifics(CpuRefreshKind::everything());
+    sys.cpus()
+        .iter()
+        .enumerate()
+        .map(|(i, cpu)| CpuCore {
+            index: i as u32,
+            usage: cpu.cpu_usage(),
+            frequency: cpu.frequency(),
+            vendor: cpu.vendor_id().to_string(),
+            brand: cpu.brand().to_string(),
+        })
+        .collect()
+}
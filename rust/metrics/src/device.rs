@@ -0,0 +1,45 @@
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+
+/// What hardware and OS a benchmark ran on
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize, serde::Deserialize)]
+pub struct DeviceInfo {
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    pub kernel_version: Option<String>,
+    pub hostname: Option<String>,
+    pub cpu_brand: String,
+    /// Logical cores
+    pub core_count: u32,
+    pub physical_core_count: Option<u32>,
+    /// Bytes
+    pub total_memory: u64,
+    pub architecture: String,
+    /// Seconds since boot
+    pub uptime: u64,
+}
+
+#[uniffi::export]
+pub fn get_device_info() -> DeviceInfo {
+    let sys = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+
+    DeviceInfo {
+        os_name: System::name(),
+        os_version: System::os_version(),
+        kernel_version: System::kernel_version(),
+        hostname: System::host_name(),
+        cpu_brand: sys
+            .cpus()
+            .first()
+            .map(|c| c.brand().to_string())
+            .unwrap_or_default(),
+        core_count: sys.cpus().len() as u32,
+        physical_core_count: System::physical_core_count().map(|n| n as u32),
+        total_memory: sys.total_memory(),
+        architecture: System::cpu_arch(),
+        uptime: System::uptime(),
+    }
+}
@@ -0,0 +1,45 @@
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum MetricsError {
+    #[error("process {pid} not found")]
+    ProcessNotFound { pid: u32 },
+
+    #[error("mark {name} not found")]
+    MarkNotFound { name: String },
+
+    #[error("io error: {reason}")]
+    Io { reason: String },
+
+    #[error("serialization error: {reason}")]
+    Serialization { reason: String },
+
+    #[error("export failed: {reason}")]
+    Export { reason: String },
+
+    #[error("invalid argument: {reason}")]
+    InvalidArgument { reason: String },
+
+    #[error("callback failed: {reason}")]
+    Callback { reason: String },
+}
+
+impl From<uniffi::UnexpectedUniFFICallbackError> for MetricsError {
+    fn from(e: uniffi::UnexpectedUniFFICallbackError) -> Self {
+        Self::Callback { reason: e.reason }
+    }
+}
+
+impl From<serde_json::Error> for MetricsError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialization {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for MetricsError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io {
+            reason: e.to_string(),
+        }
+    }
+}
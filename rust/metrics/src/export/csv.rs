@@ -0,0 +1,238 @@
+use std::{fmt::Display, fs};
+
+use crate::{MetricsError, ResourcePressure, Sample, get_device_info};
+
+const COLUMNS: &[&str] = &[
+    "timestamp",
+    "cpu",
+    "memory_total",
+    "memory_used",
+    "memory_available",
+    "memory_total_swap",
+    "memory_used_swap",
+    "process_resident",
+    "process_virtual",
+    "process_pid",
+    "process_usage",
+    "process_user",
+    "process_system",
+    "battery_level",
+    "battery_voltage",
+    "battery_current",
+    "battery_power",
+    "pressure_cpu_some_avg10",
+    "pressure_cpu_full_avg10",
+    "pressure_memory_some_avg10",
+    "pressure_memory_full_avg10",
+    "pressure_io_some_avg10",
+    "pressure_io_full_avg10",
+    "device_os_name",
+    "device_os_version",
+    "device_kernel_version",
+    "device_cpu_brand",
+    "device_core_count",
+    "device_total_memory",
+    "device_architecture",
+];
+
+/// One row per sample, with a `cpuN` column per core
+///
+/// Kinds that were not sampled are left empty. The `device_*` columns
+/// repeat the device on every row.
+#[uniffi::export]
+pub fn samples_to_csv(samples: Vec<Sample>) -> String {
+    let d = get_device_info();
+    let device = [
+        quote(d.os_name.as_deref().unwrap_or_default()),
+        quote(d.os_version.as_deref().unwrap_or_default()),
+        quote(d.kernel_version.as_deref().unwrap_or_default()),
+        quote(&d.cpu_brand),
+        d.core_count.to_string(),
+        d.total_memory.to_string(),
+        quote(&d.architecture),
+    ];
+
+    let cores = samples
+        .iter()
+        .filter_map(|s| s.cores.as_ref().map(Vec::len))
+        .max()
+        .unwrap_or(0);
+
+    let mut header: Vec<String> = COLUMNS.iter().map(|c| c.to_string()).collect();
+    header.extend((0..cores).map(|i| format!("cpu{}", i)));
+    let mut out = header.join(",") + "\n";
+
+    for s in &samples {
+        let m = s.memory.as_ref();
+        let p = s.process.as_ref();
+        let b = s.battery.as_ref();
+        let some = |r: Option<ResourcePressure>| r.map(|r| r.some.avg10);
+        let full = |r: Option<ResourcePressure>| r.and_then(|r| r.full).map(|f| f.avg10);
+        let (cpu, memory, io) = s
+            .pressure
+            .map_or((None, None, None), |p| (p.cpu, p.memory, p.io));
+        let mut row = vec![
+            s.timestamp.to_string(),
+            cell(s.cpu),
+            cell(m.map(|m| m.total)),
+            cell(m.map(|m| m.used)),
+            cell(m.map(|m| m.available)),
+            cell(m.map(|m| m.total_swap)),
+            cell(m.map(|m| m.used_swap)),
+            cell(m.map(|m| m.process_resident)),
+            cell(m.map(|m| m.process_virtual)),
+            cell(p.map(|p| p.pid)),
+            cell(p.map(|p| p.usage)),
+            cell(p.and_then(|p| p.user)),
+            cell(p.and_then(|p| p.system)),
+            cell(b.and_then(|b| b.level)),
+            cell(b.and_then(|b| b.voltage)),
+            cell(b.and_then(|b| b.current)),
+            cell(b.and_then(|b| b.power)),
+            cell(some(cpu)),
+            cell(full(cpu)),
+            cell(some(memory)),
+            cell(full(memory)),
+            cell(some(io)),
+            cell(full(io)),
+        ];
+        row.extend(device.iter().cloned());
+        row.extend(
+            (0..cores).map(|i| cell(s.cores.as_ref().and_then(|c| c.get(i)).map(|c| c.usage))),
+        );
+
+        out += &row.join(",");
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes [`samples_to_csv`] to `path`, replacing any existing file
+#[uniffi::export]
+pub fn write_samples_csv(samples: Vec<Sample>, path: String) -> Result<(), MetricsError> {
+    Ok(fs::write(path, samples_to_csv(samples))?)
+}
+
+fn cell<T: Display>(v: Option<T>) -> String {
+    v.map_or_else(String::new, |v| v.to_string())
+}
+
+/// Quotes `s` if it contains a separator, quote or line break
+fn quote(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuCore, Memory};
+
+    fn core(index: u32, usage: f32) -> CpuCore {
+        CpuCore {
+            index,
+            usage,
+            frequency: 0,
+            vendor: String::new(),
+            brand: String::new(),
+        }
+    }
+
+    fn sample(timestamp: f64) -> Sample {
+        Sample {
+            timestamp,
+            cpu: None,
+            cores: None,
+            memory: None,
+            process: None,
+            battery: None,
+            pressure: None,
+        }
+    }
+
+    /// Splits `csv` into records of unquoted fields
+    fn records(csv: &str) -> Vec<Vec<String>> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = csv.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, quoted) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => record.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                _ => field.push(c),
+            }
+        }
+        assert!(field.is_empty() && record.is_empty() && !quoted);
+        records
+    }
+
+    #[test]
+    fn rows_match_header() {
+        let mut a = sample(1.0);
+        a.cpu = Some(50.0);
+        a.cores = Some(vec![core(0, 10.0), core(1, 20.0)]);
+        let mut b = sample(2.0);
+        b.memory = Some(Memory {
+            total: 8,
+            used: 3,
+            available: 5,
+            total_swap: 0,
+            used_swap: 0,
+            process_resident: 2,
+            process_virtual: 4,
+        });
+        b.cores = Some(vec![core(0, 1.0), core(1, 2.0), core(2, 3.0), core(3, 4.0)]);
+        let c = sample(3.0);
+
+        let rows = records(&samples_to_csv(vec![a, b, c]));
+        assert_eq!(rows.len(), 4);
+        let header = &rows[0];
+        assert_eq!(header.len(), COLUMNS.len() + 4);
+        assert_eq!(header[COLUMNS.len()..], ["cpu0", "cpu1", "cpu2", "cpu3"]);
+        for row in &rows[1..] {
+            assert_eq!(row.len(), header.len());
+        }
+
+        let col = |name: &str| header.iter().position(|h| h == name).unwrap();
+        assert_eq!(rows[1][col("cpu")], "50");
+        assert_eq!(rows[1][col("memory_used")], "");
+        assert_eq!(rows[1][col("cpu1")], "20");
+        assert_eq!(rows[1][col("cpu2")], "");
+        assert_eq!(rows[2][col("cpu")], "");
+        assert_eq!(rows[2][col("memory_used")], "3");
+        assert_eq!(rows[2][col("cpu3")], "4");
+        assert_eq!(rows[3][col("timestamp")], "3");
+        assert!(rows[3][col("cpu0")..].iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn no_samples_is_header_only() {
+        assert_eq!(samples_to_csv(vec![]), COLUMNS.join(",") + "\n");
+    }
+
+    #[test]
+    fn quotes_special_characters() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote(""), "");
+        assert_eq!(quote("a,b"), "\"a,b\"");
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("a\nb"), "\"a\nb\"");
+        assert_eq!(quote("a\rb"), "\"a\rb\"");
+
+        let odd = "Brand, \"Pro\"\nrev";
+        assert_eq!(records(&format!("{},x\n", quote(odd))), [[odd, "x"]]);
+    }
+}
@@ -0,0 +1,86 @@
+use std::fs;
+
+use serde::Serialize;
+
+use crate::{DeviceInfo, MetricsError, Sample, get_device_info};
+
+#[derive(Serialize)]
+struct Line<'a> {
+    #[serde(flatten)]
+    sample: &'a Sample,
+    device: &'a DeviceInfo,
+}
+
+/// One JSON object per sample and line, unsampled kinds as `null`
+///
+/// Every line carries the device under `device`, so lines stay
+/// self-describing when files are concatenated.
+#[uniffi::export]
+pub fn samples_to_jsonl(samples: Vec<Sample>) -> Result<String, MetricsError> {
+    let device = get_device_info();
+    let mut out = String::new();
+    for sample in &samples {
+        out += &serde_json::to_string(&Line {
+            sample,
+            device: &device,
+        })?;
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Writes [`samples_to_jsonl`] to `path`, replacing any existing file
+#[uniffi::export]
+pub fn write_samples_jsonl(samples: Vec<Sample>, path: String) -> Result<(), MetricsError> {
+    Ok(fs::write(path, samples_to_jsonl(samples)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn sample(timestamp: f64) -> Sample {
+        Sample {
+            timestamp,
+            cpu: None,
+            cores: None,
+            memory: None,
+            process: None,
+            battery: None,
+            pressure: None,
+        }
+    }
+
+    #[test]
+    fn one_object_per_line() {
+        let mut a = sample(1.5);
+        a.cpu = Some(25.0);
+        let out = samples_to_jsonl(vec![a, sample(2.5)]).unwrap();
+        assert!(out.ends_with('\n'));
+
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["timestamp"], 1.5);
+        assert_eq!(lines[0]["cpu"], 25.0);
+        assert!(lines[0]["memory"].is_null());
+        assert_eq!(lines[1]["timestamp"], 2.5);
+        assert!(lines[1]["cpu"].is_null());
+        for line in &lines {
+            for kind in ["cores", "process", "battery", "pressure"] {
+                assert!(line[kind].is_null(), "{kind}");
+            }
+            assert!(line["device"]["core_count"].is_u64());
+            assert!(line["device"]["architecture"].is_string());
+        }
+    }
+
+    #[test]
+    fn no_samples_is_empty() {
+        assert_eq!(samples_to_jsonl(vec![]).unwrap(), "");
+    }
+}
@@ -0,0 +1,9 @@
+pub(crate) mod csv;
+pub(crate) mod jsonl;
+pub(crate) mod openmetrics;
+pub(crate) mod trace;
+
+pub use csv::*;
+pub use jsonl::*;
+pub use openmetrics::*;
+pub use trace::*;
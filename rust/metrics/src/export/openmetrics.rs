@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+
+use crate::{Sample, get_device_info, registry, registry_snapshot};
+
+const PREFIX: &str = "rnbench";
+const QUANTILES: [f64; 5] = [0.5, 0.9, 0.95, 0.99, 0.999];
+
+/// OpenMetrics text exposition of `sample` and everything in the registry
+///
+/// Registered histograms are exposed as summaries, and the device as
+/// the labels of a `target_info` metric. Registry names that sanitize to
+/// an existing family of another type, or to an existing series, are left out.
+#[uniffi::export]
+pub fn render_openmetrics(sample: Option<Sample>) -> String {
+    let mut out = Exposition::default();
+
+    let d = get_device_info();
+    let mut labels = vec![
+        ("architecture".to_string(), d.architecture),
+        ("core_count".to_string(), d.core_count.to_string()),
+        ("cpu_brand".to_string(), d.cpu_brand),
+    ];
+    for (key, value) in [
+        ("hostname", d.hostname),
+        ("kernel_version", d.kernel_version),
+        ("os_name", d.os_name),
+        ("os_version", d.os_version),
+    ] {
+        if let Some(value) = value {
+            labels.push((key.to_string(), value));
+        }
+    }
+    labels.push(("total_memory".to_string(), d.total_memory.to_string()));
+    out.raw_family("target", "info");
+    out.raw_sample("target_info", &labels, 1u32);
+
+    if let Some(s) = sample {
+        if let Some(cpu) = s.cpu {
+            out.family("cpu_usage_percent", "gauge");
+            out.sample("cpu_usage_percent", &[], cpu);
+        }
+        if let Some(cores) = &s.cores {
+            out.family("cpu_core_usage_percent", "gauge");
+            for c in cores {
+                out.sample(
+                    "cpu_core_usage_percent",
+                    &[("core", c.index.to_string())],
+                    c.usage,
+                );
+            }
+            out.family("cpu_core_frequency_megahertz", "gauge");
+            for c in cores {
+                out.sample(
+                    "cpu_core_frequency_megahertz",
+                    &[("core", c.index.to_string())],
+                    c.frequency,
+                );
+            }
+        }
+        if let Some(m) = &s.memory {
+            for (name, value) in [
+                ("memory_total_bytes", m.total),
+                ("memory_used_bytes", m.used),
+                ("memory_available_bytes", m.available),
+                ("swap_total_bytes", m.total_swap),
+                ("swap_used_bytes", m.used_swap),
+                ("process_resident_memory_bytes", m.process_resident),
+                ("process_virtual_memory_bytes", m.process_virtual),
+            ] {
+                out.family(name, "gauge");
+                out.sample(name, &[], value);
+            }
+        }
+        if let Some(p) = &s.process {
+            let pid = [("pid", p.pid.to_string())];
+            out.family("process_cpu_usage_percent", "gauge");
+            out.sample("process_cpu_usage_percent", &pid, p.usage);
+        }
+        if let Some(b) = &s.battery {
+            for (name, value) in [
+                ("battery_level_percent", b.level),
+                ("battery_voltage_volts", b.voltage),
+                ("battery_current_amperes", b.current),
+                ("battery_power_watts", b.power),
+            ] {
+                if let Some(value) = value {
+                    out.family(name, "gauge");
+                    out.sample(name, &[], value);
+                }
+            }
+        }
+        if let Some(p) = &s.pressure {
+            out.family("pressure_stalled_seconds", "counter");
+            for (resource, r) in [("cpu", p.cpu), ("memory", p.memory), ("io", p.io)] {
+                let Some(r) = r else { continue };
+                for (kind, stall) in [("some", Some(r.some)), ("full", r.full)] {
+                    let Some(stall) = stall else { continue };
+                    let labels = [
+                        ("resource", resource.to_string()),
+                        ("kind", kind.to_string()),
+                    ];
+                    out.sample(
+                        "pressure_stalled_seconds_total",
+                        &labels,
+                        stall.total as f64 / 1e6,
+                    );
+                }
+            }
+        }
+    }
+
+    let snapshot = registry_snapshot();
+    for c in &snapshot.counters {
+        let family = sanitize(c.name.strip_suffix("_total").unwrap_or(&c.name));
+        out.raw_family(&family, "counter");
+        out.raw_sample(&format!("{}_total", family), &sorted(&c.labels), c.value);
+    }
+
+    for g in &snapshot.gauges {
+        let family = sanitize(&g.name);
+        out.raw_family(&family, "gauge");
+        out.raw_sample(&family, &sorted(&g.labels), g.value);
+    }
+
+    for ((name, labels), h) in registry::registered_histograms() {
+        let family = sanitize(&name);
+        out.raw_family(&family, "summary");
+        for q in QUANTILES {
+            let mut labels = labels.clone();
+            labels.push(("quantile".to_string(), q.to_string()));
+            out.raw_sample(&family, &labels, h.value_at_percentile(q * 100.0));
+        }
+        out.raw_sample(
+            &format!("{}_sum", family),
+            &labels,
+            h.mean() * h.count() as f64,
+        );
+        out.raw_sample(&format!("{}_count", family), &labels, h.count());
+    }
+
+    out.finish()
+}
+
+/// Samples grouped by family, as every family may only appear once
+#[derive(Default)]
+struct Exposition {
+    families: Vec<Family>,
+    /// Family samples are added to, `None` while skipping one of a conflicting type
+    current: Option<usize>,
+}
+
+struct Family {
+    name: String,
+    kind: String,
+    /// Name with labels, and value
+    samples: Vec<(String, String)>,
+}
+
+impl Exposition {
+    /// A family of the crate's own metrics, prefixed
+    fn family(&mut self, name: &str, kind: &str) {
+        self.raw_family(&format!("{}_{}", PREFIX, name), kind);
+    }
+
+    fn sample<V: SampleValue>(&mut self, name: &str, labels: &[(&str, String)], value: V) {
+        let labels: Vec<(String, String)> = labels
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        self.raw_sample(&format!("{}_{}", PREFIX, name), &labels, value);
+    }
+
+    /// Starts adding samples to family `name`, declaring it if new
+    fn raw_family(&mut self, name: &str, kind: &str) {
+        self.current = match self.families.iter().position(|f| f.name == name) {
+            Some(i) if self.families[i].kind == kind => Some(i),
+            Some(_) => None,
+            None => {
+                self.families.push(Family {
+                    name: name.to_string(),
+                    kind: kind.to_string(),
+                    samples: vec![],
+                });
+                Some(self.families.len() - 1)
+            }
+        };
+    }
+
+    fn raw_sample<V: SampleValue>(&mut self, name: &str, labels: &[(String, String)], value: V) {
+        let Some(i) = self.current else { return };
+
+        let mut series = name.to_string();
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", sanitize(k), escape(v)))
+                .collect();
+            series += &format!("{{{}}}", labels.join(","));
+        }
+
+        let samples = &mut self.families[i].samples;
+        // distinct registry names can still sanitize to the same series
+        if samples.iter().all(|(s, _)| *s != series) {
+            samples.push((series, value.render()));
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut text = String::new();
+        for f in &self.families {
+            text += &format!("# TYPE {} {}\n", f.name, f.kind);
+            for (series, value) in &f.samples {
+                text += &format!("{} {}\n", series, value);
+            }
+        }
+        text + "# EOF\n"
+    }
+}
+
+/// Number formatted as OpenMetrics expects
+trait SampleValue {
+    fn render(self) -> String;
+}
+
+impl SampleValue for f64 {
+    fn render(self) -> String {
+        if self.is_nan() {
+            "NaN".to_string()
+        } else if self.is_infinite() {
+            if self > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl SampleValue for f32 {
+    fn render(self) -> String {
+        if self.is_finite() {
+            self.to_string()
+        } else {
+            (self as f64).render()
+        }
+    }
+}
+
+impl SampleValue for u64 {
+    fn render(self) -> String {
+        self.to_string()
+    }
+}
+
+impl SampleValue for u32 {
+    fn render(self) -> String {
+        self.to_string()
+    }
+}
+
+fn sorted(labels: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut labels: Vec<(String, String)> =
+        labels.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    labels.sort();
+    labels
+}
+
+/// Metric and label names may only contain `[a-zA-Z0-9_:]` and not start with a digit
+fn sanitize(name: &str) -> String {
+    let mut s: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{counter_inc, gauge_set};
+
+    fn type_lines(text: &str, family: &str) -> usize {
+        let prefix = format!("# TYPE {} ", family);
+        text.lines().filter(|l| l.starts_with(&prefix)).count()
+    }
+
+    #[test]
+    fn renders_non_finite_values() {
+        gauge_set("om_test_inf".to_string(), None, f64::INFINITY);
+        gauge_set("om_test_neg_inf".to_string(), None, f64::NEG_INFINITY);
+        gauge_set("om_test_nan".to_string(), None, f64::NAN);
+
+        let text = render_openmetrics(None);
+        assert!(text.contains("\nom_test_inf +Inf\n"));
+        assert!(text.contains("\nom_test_neg_inf -Inf\n"));
+        assert!(text.contains("\nom_test_nan NaN\n"));
+        assert!(!text.contains("inf\n"));
+    }
+
+    #[test]
+    fn groups_families_once() {
+        let labels = |v: &str| Some(HashMap::from([("k".to_string(), v.to_string())]));
+        gauge_set("om_test.dup".to_string(), labels("a"), 1.0);
+        gauge_set("om_test_a".to_string(), None, 2.0);
+        gauge_set("om_test_dup".to_string(), labels("b"), 3.0);
+        gauge_set("om_test-dup".to_string(), labels("b"), 4.0);
+        counter_inc("om_test_clash".to_string(), None, 1);
+        gauge_set("om_test_clash".to_string(), None, 5.0);
+
+        let text = render_openmetrics(None);
+        assert_eq!(type_lines(&text, "om_test_dup"), 1);
+        assert!(text.contains("om_test_dup{k=\"a\"} 1\n"));
+        assert_eq!(text.matches("om_test_dup{k=\"b\"}").count(), 1);
+        assert_eq!(type_lines(&text, "om_test_clash"), 1);
+        assert!(text.contains("# TYPE om_test_clash counter\n"));
+        assert!(text.contains("om_test_clash_total 1\n"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
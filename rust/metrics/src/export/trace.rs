@@ -0,0 +1,162 @@
+use std::fs;
+
+use serde_json::{Map, Value, json};
+
+use crate::{MetricsError, Sample, get_device_info, get_marks, get_measures};
+
+/// Chrome Trace Event JSON of `samples` plus every recorded mark and measure
+///
+/// Samples become counter tracks, marks instant events and measures
+/// duration events, so the file opens in chrome://tracing and Perfetto UI.
+/// The device is recorded under `metadata`.
+#[uniffi::export]
+pub fn to_chrome_trace(samples: Vec<Sample>) -> String {
+    let pid = std::process::id();
+    let mut events = vec![json!({
+        "name": "process_name",
+        "ph": "M",
+        "pid": pid,
+        "tid": 0,
+        "args": { "name": "react-native-bench" },
+    })];
+
+    let counter = |name: &str, ts: f64, args: Value| json!({ "name": name, "ph": "C", "ts": us(ts), "pid": pid, "tid": 0, "args": args });
+    for s in &samples {
+        if let Some(cpu) = s.cpu {
+            events.push(counter("cpu", s.timestamp, json!({ "usage": cpu })));
+        }
+        if let Some(cores) = &s.cores {
+            let args: Map<String, Value> = cores
+                .iter()
+                .map(|c| (format!("cpu{}", c.index), json!(c.usage)))
+                .collect();
+            events.push(counter("cpu cores", s.timestamp, Value::Object(args)));
+        }
+        if let Some(m) = &s.memory {
+            events.push(counter(
+                "memory",
+                s.timestamp,
+                json!({ "used": m.used, "used_swap": m.used_swap }),
+            ));
+            events.push(counter(
+                "process memory",
+                s.timestamp,
+                json!({ "resident": m.process_resident, "virtual": m.process_virtual }),
+            ));
+        }
+        if let Some(p) = &s.process {
+            let args = match (p.user, p.system) {
+                (Some(user), Some(system)) => json!({ "user": user, "system": system }),
+                _ => json!({ "usage": p.usage }),
+            };
+            events.push(counter("process cpu", s.timestamp, args));
+        }
+        if let Some(power) = s.battery.as_ref().and_then(|b| b.power) {
+            events.push(counter("battery", s.timestamp, json!({ "power": power })));
+        }
+        if let Some(p) = &s.pressure {
+            let mut args = Map::new();
+            for (resource, r) in [("cpu", p.cpu), ("memory", p.memory), ("io", p.io)] {
+                let Some(r) = r else { continue };
+                args.insert(format!("{} some", resource), json!(r.some.avg10));
+                if let Some(full) = r.full {
+                    args.insert(format!("{} full", resource), json!(full.avg10));
+                }
+            }
+            events.push(counter("pressure avg10", s.timestamp, Value::Object(args)));
+        }
+    }
+
+    for m in get_marks() {
+        events.push(json!({
+            "name": m.name,
+            "ph": "i",
+            "s": "p",
+            "ts": us(m.timestamp),
+            "pid": pid,
+            "tid": 0,
+        }));
+    }
+    for m in get_measures() {
+        events.push(json!({
+            "name": m.name,
+            "ph": "X",
+            "ts": us(m.start),
+            "dur": us(m.duration),
+            "pid": pid,
+            "tid": 0,
+        }));
+    }
+
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "metadata": { "device": get_device_info() },
+    })
+    .to_string()
+}
+
+/// Writes [`to_chrome_trace`] to `path`, replacing any existing file
+#[uniffi::export]
+pub fn write_chrome_trace(samples: Vec<Sample>, path: String) -> Result<(), MetricsError> {
+    Ok(fs::write(path, to_chrome_trace(samples))?)
+}
+
+/// Trace events are in microseconds, the crate's clock in milliseconds
+fn us(ms: f64) -> f64 {
+    ms * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mark, measure};
+
+    fn find<'a>(events: &'a [Value], name: &str, ph: &str) -> &'a Value {
+        events
+            .iter()
+            .find(|e| e["name"] == name && e["ph"] == ph)
+            .unwrap_or_else(|| panic!("no {ph} event {name}"))
+    }
+
+    #[test]
+    fn events_are_in_microseconds() {
+        // timings are global, so this test uses its own names
+        let start = mark("trace.start".to_string());
+        let end = mark("trace.end".to_string());
+        let m = measure(
+            "trace.span".to_string(),
+            Some("trace.start".to_string()),
+            Some("trace.end".to_string()),
+        )
+        .unwrap();
+
+        let sample = Sample {
+            timestamp: 12.5,
+            cpu: Some(40.0),
+            cores: None,
+            memory: None,
+            process: None,
+            battery: None,
+            pressure: None,
+        };
+        let trace: Value = serde_json::from_str(&to_chrome_trace(vec![sample])).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+
+        let cpu = find(events, "cpu", "C");
+        assert_eq!(cpu["ts"], 12_500.0);
+        assert_eq!(cpu["args"]["usage"], 40.0);
+        assert!(!events.iter().any(|e| e["name"] == "memory"));
+
+        let instant = find(events, "trace.start", "i");
+        assert_eq!(instant["ts"], start.timestamp * 1000.0);
+        assert_eq!(find(events, "trace.end", "i")["ts"], end.timestamp * 1000.0);
+
+        let span = find(events, "trace.span", "X");
+        assert_eq!(span["ts"], m.start * 1000.0);
+        assert_eq!(span["dur"], m.duration * 1000.0);
+
+        assert_eq!(trace["displayTimeUnit"], "ms");
+        assert!(trace["metadata"]["device"].is_object());
+    }
+}
@@ -0,0 +1,586 @@
+use std::sync::{Arc, Mutex};
+
+use crate::MetricsError;
+
+const MAGIC: &[u8; 4] = b"RNBH";
+const VERSION: u8 = 1;
+
+/// HDR-style histogram of integer values with configurable precision
+///
+/// Values are integers in a unit of the caller's choosing (e.g. µs);
+/// fractional parts are rounded when recording.
+#[derive(Debug, uniffi::Object)]
+pub struct Histogram {
+    inner: Mutex<Hdr>,
+}
+
+#[uniffi::export]
+impl Histogram {
+    /// Tracks values in `lowest..=highest` to `significant_figures` (1-5) digits
+    #[uniffi::constructor]
+    pub fn new(lowest: u64, highest: u64, significant_figures: u8) -> Result<Self, MetricsError> {
+        Ok(Self {
+            inner: Mutex::new(Hdr::new(lowest, highest, significant_figures)?),
+        })
+    }
+
+    /// Reads a histogram written by [`Histogram::serialize`]
+    #[uniffi::constructor]
+    pub fn deserialize(bytes: Vec<u8>) -> Result<Self, MetricsError> {
+        Ok(Self {
+            inner: Mutex::new(Hdr::decode(&bytes)?),
+        })
+    }
+
+    pub fn record(&self, value: f64) -> Result<(), MetricsError> {
+        self.record_n(value, 1)
+    }
+
+    /// Records `value` `count` times
+    pub fn record_n(&self, value: f64, count: u64) -> Result<(), MetricsError> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(MetricsError::InvalidArgument {
+                reason: format!("cannot record {}", value),
+            });
+        }
+        self.inner
+            .lock()
+            .unwrap()
+            .record(value.round() as u64, count)
+    }
+
+    /// Adds every value recorded in `other`
+    pub fn merge(&self, other: Arc<Histogram>) -> Result<(), MetricsError> {
+        // copied first, so merging a histogram into itself does not deadlock
+        let other = other.inner.lock().unwrap().clone();
+        let mut inner = self.inner.lock().unwrap();
+        if other.total == 0 {
+            return Ok(());
+        }
+
+        // every bucket is mapped before any is added, so a failed merge changes nothing
+        let out_of_range = || MetricsError::InvalidArgument {
+            reason: format!(
+                "{} outside trackable range {}..={}",
+                other.max, inner.lowest, inner.highest
+            ),
+        };
+        if other.max > inner.highest {
+            return Err(out_of_range());
+        }
+        let indices = other
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+            .map(|(i, &c)| Some((inner.index_of(other.value_from_index(i))?, c)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(out_of_range)?;
+        inner.total = inner.total.checked_add(other.total).ok_or_else(overflow)?;
+
+        // no bucket can exceed the total, so these cannot overflow either
+        for (index, count) in indices {
+            inner.counts[index] += count;
+        }
+        // buckets only keep their lowest value, so take the exact extremes from `other`
+        inner.min = inner.min.min(other.min);
+        inner.max = inner.max.max(other.max);
+        Ok(())
+    }
+
+    /// Value at percentile `p` (0-100), 0 when empty
+    pub fn value_at_percentile(&self, p: f64) -> f64 {
+        self.inner.lock().unwrap().value_at_percentile(p) as f64
+    }
+
+    pub fn count(&self) -> u64 {
+        self.inner.lock().unwrap().total
+    }
+
+    /// Smallest recorded value, 0 when empty
+    pub fn min(&self) -> f64 {
+        let inner = self.inner.lock().unwrap();
+        if inner.total == 0 {
+            0.0
+        } else {
+            inner.min as f64
+        }
+    }
+
+    /// Largest recorded value, 0 when empty
+    pub fn max(&self) -> f64 {
+        self.inner.lock().unwrap().max as f64
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.inner.lock().unwrap().mean()
+    }
+
+    pub fn stddev(&self) -> f64 {
+        self.inner.lock().unwrap().stddev()
+    }
+
+    pub fn reset(&self) {
+        self.inner.lock().unwrap().reset();
+    }
+
+    /// Compact binary form, counts run-length and varint encoded
+    pub fn serialize(&self) -> Vec<u8> {
+        self.inner.lock().unwrap().encode()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Hdr {
+    lowest: u64,
+    highest: u64,
+    significant_figures: u8,
+
+    unit_magnitude: u32,
+    sub_bucket_half_count_magnitude: u32,
+    sub_bucket_half_count: usize,
+    sub_bucket_mask: u64,
+    leading_zero_count_base: u32,
+
+    counts: Vec<u64>,
+    total: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Hdr {
+    fn new(lowest: u64, highest: u64, significant_figures: u8) -> Result<Self, MetricsError> {
+        let invalid = |reason: &str| MetricsError::InvalidArgument {
+            reason: reason.to_string(),
+        };
+        if lowest < 1 {
+            return Err(invalid("lowest must be at least 1"));
+        }
+        if highest < lowest.saturating_mul(2) {
+            return Err(invalid("highest must be at least twice lowest"));
+        }
+        if !(1..=5).contains(&significant_figures) {
+            return Err(invalid("significant figures must be within 1-5"));
+        }
+
+        let largest_single_unit = 2 * 10u64.pow(significant_figures as u32);
+        let sub_bucket_count_magnitude = (largest_single_unit as f64).log2().ceil() as u32;
+        let sub_bucket_half_count_magnitude = sub_bucket_count_magnitude.max(1) - 1;
+        let sub_bucket_count = 1u64 << (sub_bucket_half_count_magnitude + 1);
+        let unit_magnitude = lowest.ilog2();
+        // values are shifted by both magnitudes, which must leave room in a u64
+        if unit_magnitude + sub_bucket_half_count_magnitude > 61 {
+            return Err(invalid(
+                "lowest is too large for the requested significant figures",
+            ));
+        }
+
+        // buckets needed to cover `highest`
+        let mut smallest_untrackable = sub_bucket_count << unit_magnitude;
+        let mut bucket_count = 1;
+        while smallest_untrackable <= highest {
+            if smallest_untrackable > u64::MAX / 2 {
+                bucket_count += 1;
+                break;
+            }
+            smallest_untrackable <<= 1;
+            bucket_count += 1;
+        }
+
+        let sub_bucket_half_count = (sub_bucket_count / 2) as usize;
+        Ok(Self {
+            lowest,
+            highest,
+            significant_figures,
+            unit_magnitude,
+            sub_bucket_half_count_magnitude,
+            sub_bucket_half_count,
+            sub_bucket_mask: (sub_bucket_count - 1) << unit_magnitude,
+            leading_zero_count_base: 64 - unit_magnitude - sub_bucket_half_count_magnitude - 1,
+            counts: vec![0; (bucket_count + 1) * sub_bucket_half_count],
+            total: 0,
+            min: u64::MAX,
+            max: 0,
+        })
+    }
+
+    fn record(&mut self, value: u64, count: u64) -> Result<(), MetricsError> {
+        let index = self
+            .index_of(value)
+            .filter(|_| value <= self.highest)
+            .ok_or_else(|| MetricsError::InvalidArgument {
+                reason: format!(
+                    "{} outside trackable range {}..={}",
+                    value, self.lowest, self.highest
+                ),
+            })?;
+        self.total = self.total.checked_add(count).ok_or_else(overflow)?;
+
+        // no bucket can exceed the total, so this cannot overflow either
+        self.counts[index] += count;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.counts.fill(0);
+        self.total = 0;
+        self.min = u64::MAX;
+        self.max = 0;
+    }
+
+    fn bucket_index(&self, value: u64) -> u32 {
+        self.leading_zero_count_base - (value | self.sub_bucket_mask).leading_zeros()
+    }
+
+    fn index_of(&self, value: u64) -> Option<usize> {
+        let bucket = self.bucket_index(value);
+        let sub_bucket = (value >> (bucket + self.unit_magnitude)) as usize;
+        let index = ((bucket as usize + 1) << self.sub_bucket_half_count_magnitude) + sub_bucket
+            - self.sub_bucket_half_count;
+        (index < self.counts.len()).then_some(index)
+    }
+
+    fn value_from_index(&self, index: usize) -> u64 {
+        let mut bucket = (index >> self.sub_bucket_half_count_magnitude) as i64 - 1;
+        let mut sub_bucket =
+            (index & (self.sub_bucket_half_count - 1)) + self.sub_bucket_half_count;
+        if bucket < 0 {
+            sub_bucket -= self.sub_bucket_half_count;
+            bucket = 0;
+        }
+        (sub_bucket as u64) << (bucket as u32 + self.unit_magnitude)
+    }
+
+    fn equivalent_range(&self, value: u64) -> u64 {
+        let bucket = self.bucket_index(value);
+        let sub_bucket = value >> (bucket + self.unit_magnitude);
+        let adjusted = if sub_bucket >= 2 * self.sub_bucket_half_count as u64 {
+            bucket + 1
+        } else {
+            bucket
+        };
+        1 << (self.unit_magnitude + adjusted)
+    }
+
+    fn lowest_equivalent(&self, value: u64) -> u64 {
+        let bucket = self.bucket_index(value);
+        let sub_bucket = value >> (bucket + self.unit_magnitude);
+        sub_bucket << (bucket + self.unit_magnitude)
+    }
+
+    fn highest_equivalent(&self, value: u64) -> u64 {
+        self.lowest_equivalent(value) + self.equivalent_range(value) - 1
+    }
+
+    fn median_equivalent(&self, value: u64) -> u64 {
+        self.lowest_equivalent(value) + self.equivalent_range(value) / 2
+    }
+
+    fn value_at_percentile(&self, p: f64) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+
+        let target = ((p.clamp(0.0, 100.0) / 100.0 * self.total as f64).round() as u64).max(1);
+        let mut seen = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return self
+                    .highest_equivalent(self.value_from_index(i))
+                    .min(self.max);
+            }
+        }
+        self.max
+    }
+
+    fn values(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+            .map(|(i, c)| {
+                (
+                    self.median_equivalent(self.value_from_index(i)) as f64,
+                    *c as f64,
+                )
+            })
+    }
+
+    fn mean(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.values().map(|(v, c)| v * c).sum::<f64>() / self.total as f64
+    }
+
+    fn stddev(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let var = self
+            .values()
+            .map(|(v, c)| (v - mean).powi(2) * c)
+            .sum::<f64>()
+            / self.total as f64;
+        var.sqrt()
+    }
+
+    /// Header followed by counts as zig-zag varints,
+    /// where negative numbers are runs of empty buckets
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(32);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.significant_figures);
+        write_varint(&mut out, self.lowest);
+        write_varint(&mut out, self.highest);
+        write_varint(&mut out, self.min);
+        write_varint(&mut out, self.max);
+
+        let used = self
+            .counts
+            .iter()
+            .rposition(|c| *c > 0)
+            .map_or(0, |i| i + 1);
+        let mut zeros: i64 = 0;
+        for &count in &self.counts[..used] {
+            if count == 0 {
+                zeros += 1;
+                continue;
+            }
+            if zeros > 0 {
+                write_varint(&mut out, zigzag(-zeros));
+                zeros = 0;
+            }
+            write_varint(&mut out, zigzag(count as i64));
+        }
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, MetricsError> {
+        let malformed = || MetricsError::InvalidArgument {
+            reason: "malformed histogram".to_string(),
+        };
+        if bytes.len() < 6 || &bytes[..4] != MAGIC || bytes[4] != VERSION {
+            return Err(malformed());
+        }
+
+        let mut pos = 6;
+        let lowest = read_varint(bytes, &mut pos).ok_or_else(malformed)?;
+        let highest = read_varint(bytes, &mut pos).ok_or_else(malformed)?;
+        let mut hdr = Self::new(lowest, highest, bytes[5])?;
+        hdr.min = read_varint(bytes, &mut pos).ok_or_else(malformed)?;
+        hdr.max = read_varint(bytes, &mut pos).ok_or_else(malformed)?;
+
+        let mut index: usize = 0;
+        while pos < bytes.len() {
+            let n = unzigzag(read_varint(bytes, &mut pos).ok_or_else(malformed)?);
+            if n < 0 {
+                index = index.saturating_add(n.unsigned_abs() as usize);
+                continue;
+            }
+            if index >= hdr.counts.len() {
+                return Err(malformed());
+            }
+
+            hdr.counts[index] = n as u64;
+            hdr.total = hdr.total.checked_add(n as u64).ok_or_else(malformed)?;
+            index += 1;
+        }
+        Ok(hdr)
+    }
+}
+
+fn overflow() -> MetricsError {
+    MetricsError::InvalidArgument {
+        reason: "histogram count would overflow".to_string(),
+    }
+}
+
+fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let b = *bytes.get(*pos)?;
+        *pos += 1;
+        n |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Some(n);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram(values: &[f64]) -> Histogram {
+        let h = Histogram::new(1, 3_600_000_000, 3).unwrap();
+        for &v in values {
+            h.record(v).unwrap();
+        }
+        h
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(Histogram::new(0, 100, 3).is_err());
+        assert!(Histogram::new(10, 19, 3).is_err());
+        assert!(Histogram::new(1, 100, 0).is_err());
+        assert!(Histogram::new(1, 100, 6).is_err());
+        assert!(Histogram::new(1 << 50, u64::MAX, 5).is_err());
+        assert!(Histogram::new(1 << 44, u64::MAX, 5).is_ok());
+        assert!(Histogram::new(1, u64::MAX, 5).is_ok());
+    }
+
+    #[test]
+    fn rejects_values_out_of_range() {
+        let h = Histogram::new(1, 1000, 3).unwrap();
+        assert!(h.record(1001.0).is_err());
+        assert!(h.record(-1.0).is_err());
+        assert!(h.record(f64::NAN).is_err());
+        assert_eq!(h.count(), 0);
+    }
+
+    #[test]
+    fn percentiles_within_precision() {
+        for figures in 1..=5 {
+            let h = Histogram::new(1, 1_000_000, figures).unwrap();
+            for v in 1..=100_000 {
+                h.record(v as f64).unwrap();
+            }
+
+            let tolerance = 10f64.powi(-(figures as i32));
+            for p in [1.0, 25.0, 50.0, 90.0, 99.0, 99.9] {
+                let expected = p / 100.0 * 100_000.0;
+                let actual = h.value_at_percentile(p);
+                assert!(
+                    (actual - expected).abs() / expected <= tolerance,
+                    "p{} with {} figures: {} vs {}",
+                    p,
+                    figures,
+                    actual,
+                    expected
+                );
+            }
+            assert_eq!(h.value_at_percentile(100.0), 100_000.0);
+        }
+    }
+
+    #[test]
+    fn tracks_exact_extremes() {
+        let h = histogram(&[12345.0, 67891.0]);
+        assert_eq!(h.min(), 12345.0);
+        assert_eq!(h.max(), 67891.0);
+        assert_eq!(h.count(), 2);
+    }
+
+    #[test]
+    fn merge_adds_counts_and_keeps_extremes() {
+        let a = histogram(&[100.0, 200.0]);
+        let b = histogram(&[12345.0, 50.0, 50.0]);
+        a.merge(Arc::new(b)).unwrap();
+        assert_eq!(a.count(), 5);
+        assert_eq!(a.min(), 50.0);
+        assert_eq!(a.max(), 12345.0);
+        assert_eq!(a.value_at_percentile(40.0), 50.0);
+
+        let empty = histogram(&[]);
+        empty.merge(Arc::new(histogram(&[12345.0]))).unwrap();
+        assert_eq!(empty.min(), 12345.0);
+        assert_eq!(empty.max(), 12345.0);
+    }
+
+    #[test]
+    fn rejects_count_overflow() {
+        let h = histogram(&[]);
+        h.record_n(10.0, u64::MAX).unwrap();
+        assert!(h.record(10.0).is_err());
+        assert!(h.record(20.0).is_err());
+        assert_eq!(h.count(), u64::MAX);
+        assert_eq!(h.max(), 10.0);
+
+        let other = histogram(&[10.0, 30.0]);
+        assert!(h.merge(Arc::new(other)).is_err());
+        assert_eq!(h.count(), u64::MAX);
+        assert_eq!(h.max(), 10.0);
+        assert_eq!(h.value_at_percentile(100.0), 10.0);
+    }
+
+    #[test]
+    fn merge_into_itself() {
+        let h = Arc::new(histogram(&[10.0, 20.0]));
+        h.merge(h.clone()).unwrap();
+        assert_eq!(h.count(), 4);
+    }
+
+    #[test]
+    fn failed_merge_changes_nothing() {
+        let small = Histogram::new(1, 1000, 3).unwrap();
+        small.record(500.0).unwrap();
+        let large = histogram(&[10.0, 5000.0]);
+        assert!(small.merge(Arc::new(large)).is_err());
+        assert_eq!(small.count(), 1);
+        assert_eq!(small.min(), 500.0);
+        assert_eq!(small.max(), 500.0);
+    }
+
+    #[test]
+    fn serialization_round_trips() {
+        let h = histogram(&[1.0, 7.0, 7.0, 1500.0, 98765.0, 3_000_000_000.0]);
+        let bytes = h.serialize();
+        let copy = Histogram::deserialize(bytes.clone()).unwrap();
+
+        assert_eq!(copy.serialize(), bytes);
+        assert_eq!(copy.count(), h.count());
+        assert_eq!(copy.min(), h.min());
+        assert_eq!(copy.max(), h.max());
+        assert_eq!(copy.mean(), h.mean());
+        for p in [0.0, 33.0, 50.0, 99.0, 100.0] {
+            assert_eq!(copy.value_at_percentile(p), h.value_at_percentile(p));
+        }
+
+        let empty = histogram(&[]);
+        let copy = Histogram::deserialize(empty.serialize()).unwrap();
+        assert_eq!(copy.count(), 0);
+        assert_eq!(copy.min(), 0.0);
+    }
+
+    #[test]
+    fn deserialize_rejects_malformed_bytes() {
+        assert!(Histogram::deserialize(vec![]).is_err());
+        assert!(Histogram::deserialize(b"RNBX\x01\x03".to_vec()).is_err());
+
+        // lowest = 2^50 with 5 figures would not fit a u64
+        let mut bytes = b"RNBH\x01\x05".to_vec();
+        write_varint(&mut bytes, 1 << 50);
+        write_varint(&mut bytes, u64::MAX);
+        assert!(Histogram::deserialize(bytes).is_err());
+
+        // a count past the last bucket
+        let mut bytes = histogram(&[]).serialize();
+        write_varint(&mut bytes, zigzag(-(i64::MAX / 2)));
+        write_varint(&mut bytes, zigzag(1));
+        assert!(Histogram::deserialize(bytes).is_err());
+    }
+}
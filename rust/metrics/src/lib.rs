@@ -1,6 +1,64 @@
 use std::{ffi::c_float, thread};
 
-use sysinfo::System;
+use sysinfo::{CpuRefreshKind, RefreshKind, System};
+
+mod battery;
+mod bench;
+mod blocking;
+mod clock;
+mod compare;
+mod counters;
+mod cpu;
+mod device;
+mod error;
+mod export;
+mod histogram;
+mod memory;
+#[cfg(feature = "otlp")]
+mod otlp;
+mod outliers;
+mod process;
+mod psi;
+mod registry;
+mod sampler;
+mod session;
+mod stats;
+mod store;
+mod sysfs;
+mod thermal;
+mod threads;
+mod timing;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod procfs;
+#[cfg(feature = "server")]
+mod server;
+
+pub use battery::*;
+pub use bench::*;
+pub use compare::*;
+pub use counters::*;
+pub use cpu::*;
+pub use device::*;
+pub use error::*;
+pub use export::*;
+pub use histogram::*;
+pub use memory::*;
+#[cfg(feature = "otlp")]
+pub use otlp::*;
+pub use outliers::*;
+pub use process::*;
+pub use psi::*;
+pub use registry::*;
+pub use sampler::*;
+#[cfg(feature = "server")]
+pub use server::*;
+pub use session::*;
+pub use stats::*;
+pub use store::*;
+pub use thermal::*;
+pub use threads::*;
+pub use timing::*;
 
 uniffi::setup_scaffolding!();
 
@@ -11,7 +69,9 @@ pub struct Metrics {
 
 #[uniffi::export]
 pub fn get_cpu() -> Metrics {
-    let mut sys = System::new_all();
+    let mut sys = System::new_with_specifics(
+        RefreshKind::nothing().with_cpu(CpuRefreshKind::nothing().with_cpu_usage()),
+    );
     thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
     sys.refresh_cpu_usage();
 
@@ -19,6 +79,12 @@ pub fn get_cpu() -> Metrics {
         cpu: sys.global_cpu_usage(),
     }
 }
+
+/// Non-blocking [`get_cpu`], measured on a background thread
+#[uniffi::export]
+pub async fn get_cpu_async() -> Metrics {
+    blocking::spawn(get_cpu).await
+}
 //
 // #[cfg(test)]
 // mod tests {
@@ -0,0 +1,56 @@
+use sysinfo::{MemoryRefreshKind, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::blocking;
+
+/// Memory usage of the device and of the current app process, in bytes
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct Memory {
+    pub total: u64,
+    pub used: u64,
+    pub available: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    /// Resident set size of the current process
+    pub process_resident: u64,
+    /// Virtual memory size of the current process
+    pub process_virtual: u64,
+}
+
+#[uniffi::export]
+pub fn get_memory() -> Memory {
+    read_memory(&mut System::new())
+}
+
+/// Non-blocking [`get_memory`], read on a background thread
+#[uniffi::export]
+pub async fn get_memory_async() -> Memory {
+    blocking::spawn(get_memory).await
+}
+
+/// Refreshes only RAM, swap and the current process' memory on `sys`
+pub(crate) fn read_memory(sys: &mut System) -> Memory {
+    sys.refresh_memory_specifics(MemoryRefreshKind::everything());
+
+    let (process_resident, process_virtual) = match sysinfo::get_current_pid() {
+        Ok(pid) => {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                false,
+                ProcessRefreshKind::nothing().with_memory(),
+            );
+            sys.process(pid)
+                .map_or((0, 0), |p| (p.memory(), p.virtual_memory()))
+        }
+        Err(_) => (0, 0),
+    };
+
+    Memory {
+        total: sys.total_memory(),
+        used: sys.used_memory(),
+        available: sys.available_memory(),
+        total_swap: sys.total_swap(),
+        used_swap: sys.used_swap(),
+        process_resident,
+        process_virtual,
+    }
+}
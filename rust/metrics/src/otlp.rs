@@ -0,0 +1,471 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    DeviceInfo, Measure, MetricsError, Sample, blocking, clock, get_device_info, stats::Rng,
+};
+
+const SCOPE: &str = "react-native-bench";
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct OtlpConfig {
+    /// Collector base URL, e.g. `http://10.0.2.2:4318`
+    pub endpoint: String,
+    #[uniffi(default = "react-native-bench")]
+    pub service_name: String,
+    /// Extra HTTP headers, e.g. for authentication
+    #[uniffi(default = None)]
+    pub headers: Option<HashMap<String, String>>,
+    /// Samples or measures queued before they are sent
+    #[uniffi(default = 100)]
+    pub batch_size: u32,
+    #[uniffi(default = 3)]
+    pub max_retries: u32,
+    /// Doubled after every failed attempt
+    #[uniffi(default = 500)]
+    pub retry_backoff_ms: u32,
+    #[uniffi(default = 10000)]
+    pub timeout_ms: u32,
+}
+
+/// Pushes samples as OTel gauges and measures as spans over OTLP/HTTP JSON
+///
+/// Data is batched until `batch_size` is reached or [`OtlpExporter::flush`]
+/// is called. Batches still failing after every retry are dropped.
+/// Requests and retry backoff run on a background thread.
+#[derive(Debug, uniffi::Object)]
+pub struct OtlpExporter {
+    config: OtlpConfig,
+    agent: ureq::Agent,
+    device: DeviceInfo,
+    pending: Mutex<Pending>,
+    rng: Mutex<Rng>,
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    samples: Vec<Sample>,
+    measures: Vec<Measure>,
+}
+
+#[uniffi::export]
+impl OtlpExporter {
+    #[uniffi::constructor]
+    pub fn new(config: OtlpConfig) -> Self {
+        let agent = ureq::Agent::new_with_config(
+            ureq::Agent::config_builder()
+                .timeout_global(Some(Duration::from_millis(config.timeout_ms as u64)))
+                .http_status_as_error(false)
+                .build(),
+        );
+
+        Self {
+            config,
+            agent,
+            device: get_device_info(),
+            pending: Mutex::new(Pending::default()),
+            rng: Mutex::new(Rng::new(
+                clock::to_unix_nanos(clock::now_ms()) ^ std::process::id() as u64,
+            )),
+        }
+    }
+
+    pub async fn push_samples(self: Arc<Self>, samples: Vec<Sample>) -> Result<(), MetricsError> {
+        blocking::spawn(move || self.push_samples_blocking(samples)).await
+    }
+
+    pub async fn push_measures(
+        self: Arc<Self>,
+        measures: Vec<Measure>,
+    ) -> Result<(), MetricsError> {
+        blocking::spawn(move || self.push_measures_blocking(measures)).await
+    }
+
+    /// Sends everything queued
+    pub async fn flush(self: Arc<Self>) -> Result<(), MetricsError> {
+        blocking::spawn(move || self.flush_blocking()).await
+    }
+}
+
+impl OtlpExporter {
+    fn push_samples_blocking(&self, samples: Vec<Sample>) -> Result<(), MetricsError> {
+        let batch = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.samples.extend(samples);
+            self.take_full(&mut pending.samples)
+        };
+        self.send_samples(batch)
+    }
+
+    fn push_measures_blocking(&self, measures: Vec<Measure>) -> Result<(), MetricsError> {
+        let batch = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.measures.extend(measures);
+            self.take_full(&mut pending.measures)
+        };
+        self.send_measures(batch)
+    }
+
+    fn flush_blocking(&self) -> Result<(), MetricsError> {
+        let pending = mem::take(&mut *self.pending.lock().unwrap());
+        let samples = self.send_samples(pending.samples);
+        let measures = self.send_measures(pending.measures);
+        samples.and(measures)
+    }
+
+    /// Takes the queue once it reaches the batch size
+    fn take_full<T>(&self, queue: &mut Vec<T>) -> Vec<T> {
+        if queue.len() >= self.config.batch_size as usize {
+            mem::take(queue)
+        } else {
+            vec![]
+        }
+    }
+
+    /// Service and device, using OTel semantic convention keys
+    fn resource(&self) -> Value {
+        let d = &self.device;
+        let mut attributes = vec![
+            attribute("service.name", &self.config.service_name),
+            attribute("host.arch", &d.architecture),
+            attribute("host.cpu.model.name", &d.cpu_brand),
+        ];
+        for (key, value) in [
+            ("os.name", &d.os_name),
+            ("os.version", &d.os_version),
+            ("host.name", &d.hostname),
+        ] {
+            if let Some(value) = value {
+                attributes.push(attribute(key, value));
+            }
+        }
+        json!({ "attributes": attributes })
+    }
+
+    fn send_samples(&self, samples: Vec<Sample>) -> Result<(), MetricsError> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+
+        let mut series: Vec<(&str, &str, Vec<Value>)> = vec![
+            ("rnbench.cpu.usage", "%", vec![]),
+            ("rnbench.cpu.core.usage", "%", vec![]),
+            ("rnbench.cpu.core.frequency", "MHz", vec![]),
+            ("rnbench.memory.used", "By", vec![]),
+            ("rnbench.memory.available", "By", vec![]),
+            ("rnbench.swap.used", "By", vec![]),
+            ("rnbench.process.memory.resident", "By", vec![]),
+            ("rnbench.process.memory.virtual", "By", vec![]),
+            ("rnbench.process.cpu.usage", "%", vec![]),
+            ("rnbench.battery.level", "%", vec![]),
+            ("rnbench.battery.power", "W", vec![]),
+            ("rnbench.pressure.avg10", "%", vec![]),
+        ];
+        for s in &samples {
+            let time = clock::to_unix_nanos(s.timestamp);
+            let mut push = |name: &str, value: f64, attrs: Vec<Value>| {
+                if let Some((_, _, points)) = series.iter_mut().find(|(n, _, _)| *n == name) {
+                    points.push(json!({
+                        "timeUnixNano": time.to_string(),
+                        "asDouble": value,
+                        "attributes": attrs,
+                    }));
+                }
+            };
+
+            if let Some(cpu) = s.cpu {
+                push("rnbench.cpu.usage", cpu as f64, vec![]);
+            }
+            for c in s.cores.iter().flatten() {
+                let core = vec![attribute("core", &c.index.to_string())];
+                push("rnbench.cpu.core.usage", c.usage as f64, core.clone());
+                push("rnbench.cpu.core.frequency", c.frequency as f64, core);
+            }
+            if let Some(m) = &s.memory {
+                push("rnbench.memory.used", m.used as f64, vec![]);
+                push("rnbench.memory.available", m.available as f64, vec![]);
+                push("rnbench.swap.used", m.used_swap as f64, vec![]);
+                push(
+                    "rnbench.process.memory.resident",
+                    m.process_resident as f64,
+                    vec![],
+                );
+                push(
+                    "rnbench.process.memory.virtual",
+                    m.process_virtual as f64,
+                    vec![],
+                );
+            }
+            if let Some(p) = &s.process {
+                let pid = vec![attribute("pid", &p.pid.to_string())];
+                push("rnbench.process.cpu.usage", p.usage as f64, pid);
+            }
+            if let Some(b) = &s.battery {
+                if let Some(level) = b.level {
+                    push("rnbench.battery.level", level, vec![]);
+                }
+                if let Some(power) = b.power {
+                    push("rnbench.battery.power", power, vec![]);
+                }
+            }
+            if let Some(p) = &s.pressure {
+                for (resource, r) in [("cpu", p.cpu), ("memory", p.memory), ("io", p.io)] {
+                    let Some(r) = r else { continue };
+                    for (kind, stall) in [("some", Some(r.some)), ("full", r.full)] {
+                        let Some(stall) = stall else { continue };
+                        let attrs = vec![attribute("resource", resource), attribute("kind", kind)];
+                        push("rnbench.pressure.avg10", stall.avg10, attrs);
+                    }
+                }
+            }
+        }
+
+        let metrics: Vec<Value> = series
+            .into_iter()
+            .filter(|(_, _, points)| !points.is_empty())
+            .map(|(name, unit, points)| {
+                json!({ "name": name, "unit": unit, "gauge": { "dataPoints": points } })
+            })
+            .collect();
+
+        let body = json!({
+            "resourceMetrics": [{
+                "resource": self.resource(),
+                "scopeMetrics": [{ "scope": scope(), "metrics": metrics }],
+            }],
+        });
+        self.post("/v1/metrics", body)
+    }
+
+    fn send_measures(&self, measures: Vec<Measure>) -> Result<(), MetricsError> {
+        if measures.is_empty() {
+            return Ok(());
+        }
+
+        let spans: Vec<Value> = {
+            let mut rng = self.rng.lock().unwrap();
+            let trace_id = format!("{:016x}{:016x}", rng.next_u64(), rng.next_u64());
+            measures
+                .iter()
+                .map(|m| {
+                    json!({
+                        "traceId": trace_id,
+                        "spanId": format!("{:016x}", rng.next_u64()),
+                        "name": m.name,
+                        // SPAN_KIND_INTERNAL
+                        "kind": 1,
+                        "startTimeUnixNano": clock::to_unix_nanos(m.start).to_string(),
+                        "endTimeUnixNano": clock::to_unix_nanos(m.start + m.duration).to_string(),
+                    })
+                })
+                .collect()
+        };
+
+        let body = json!({
+            "resourceSpans": [{
+                "resource": self.resource(),
+                "scopeSpans": [{ "scope": scope(), "spans": spans }],
+            }],
+        });
+        self.post("/v1/traces", body)
+    }
+
+    /// Retries network errors, 429 and 5xx with exponential backoff
+    fn post(&self, path: &str, body: Value) -> Result<(), MetricsError> {
+        let url = format!("{}{}", self.config.endpoint.trim_end_matches('/'), path);
+        let body = body.to_string();
+
+        let mut backoff = Duration::from_millis(self.config.retry_backoff_ms as u64);
+        let mut attempt = 0;
+        loop {
+            let mut req = self
+                .agent
+                .post(&url)
+                .header("Content-Type", "application/json");
+            for (k, v) in self.config.headers.iter().flatten() {
+                req = req.header(k, v);
+            }
+
+            let (retryable, reason) = match req.send(&body) {
+                Ok(res) if res.status().is_success() => return Ok(()),
+                Ok(res) => {
+                    let status = res.status();
+                    (
+                        status.as_u16() == 429 || status.is_server_error(),
+                        format!("{} responded {}", url, status),
+                    )
+                }
+                Err(e) => (true, format!("{}: {}", url, e)),
+            };
+
+            if !retryable || attempt >= self.config.max_retries {
+                return Err(MetricsError::Export { reason });
+            }
+            thread::sleep(backoff);
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+fn scope() -> Value {
+    json!({ "name": SCOPE, "version": env!("CARGO_PKG_VERSION") })
+}
+
+fn attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::blocking::block_on;
+
+    /// Collector stand-in answering each request with the next status,
+    /// yielding the path and JSON body of every request
+    fn start_collector(statuses: Vec<u16>) -> (String, thread::JoinHandle<Vec<(String, Value)>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+            for status in statuses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split_whitespace().nth(1).unwrap().to_string();
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    let header = header.to_ascii_lowercase();
+                    if let Some(v) = header.strip_prefix("content-length:") {
+                        length = v.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                requests.push((path, serde_json::from_slice(&body).unwrap()));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (endpoint, handle)
+    }
+
+    fn new_exporter(endpoint: String, max_retries: u32) -> Arc<OtlpExporter> {
+        Arc::new(OtlpExporter::new(OtlpConfig {
+            endpoint,
+            service_name: "test".to_string(),
+            headers: None,
+            batch_size: 100,
+            max_retries,
+            retry_backoff_ms: 1,
+            timeout_ms: 5000,
+        }))
+    }
+
+    fn sample(cpu: f32) -> Sample {
+        Sample {
+            timestamp: 1.0,
+            cpu: Some(cpu),
+            cores: None,
+            memory: None,
+            process: None,
+            battery: None,
+            pressure: None,
+        }
+    }
+
+    #[test]
+    fn sends_gauges_and_retries_server_errors() {
+        let (endpoint, collector) = start_collector(vec![503, 200]);
+        let exporter = new_exporter(endpoint, 3);
+        block_on(
+            exporter
+                .clone()
+                .push_samples(vec![sample(12.5), sample(50.0)]),
+        )
+        .unwrap();
+        block_on(exporter.flush()).unwrap();
+
+        let requests = collector.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], requests[1]);
+
+        let (path, body) = &requests[1];
+        assert_eq!(path, "/v1/metrics");
+        let resource = &body["resourceMetrics"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0],
+            json!({ "key": "service.name", "value": { "stringValue": "test" } })
+        );
+        let metrics = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0]["name"], "rnbench.cpu.usage");
+        let points = metrics[0]["gauge"]["dataPoints"].as_array().unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0]["asDouble"], 12.5);
+        assert_eq!(points[1]["asDouble"], 50.0);
+    }
+
+    #[test]
+    fn sends_measures_as_spans() {
+        let (endpoint, collector) = start_collector(vec![200]);
+        let exporter = new_exporter(endpoint, 0);
+        let measure = Measure {
+            name: "startup".to_string(),
+            start: 10.0,
+            duration: 5.0,
+        };
+        block_on(exporter.clone().push_measures(vec![measure])).unwrap();
+        block_on(exporter.flush()).unwrap();
+
+        let (path, body) = &collector.join().unwrap()[0];
+        assert_eq!(path, "/v1/traces");
+        let span = &body["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["name"], "startup");
+        let start: u64 = span["startTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        let end: u64 = span["endTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        assert_eq!(end - start, 5_000_000);
+    }
+
+    #[test]
+    fn gives_up_after_retries_and_on_client_errors() {
+        let (endpoint, collector) = start_collector(vec![500, 500]);
+        let exporter = new_exporter(endpoint, 1);
+        block_on(exporter.clone().push_samples(vec![sample(1.0)])).unwrap();
+        let err = block_on(exporter.flush()).unwrap_err();
+        assert!(matches!(err, MetricsError::Export { .. }));
+        assert_eq!(collector.join().unwrap().len(), 2);
+
+        let (endpoint, collector) = start_collector(vec![400]);
+        let exporter = new_exporter(endpoint, 3);
+        block_on(exporter.clone().push_samples(vec![sample(1.0)])).unwrap();
+        assert!(block_on(exporter.flush()).is_err());
+        assert_eq!(collector.join().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{MetricsError, stats};
+
+/// Iglewicz & Hoaglin's cutoff for the modified z-score
+const MODIFIED_Z_MILD: f64 = 3.5;
+const MODIFIED_Z_SEVERE: f64 = 7.0;
+/// Scales the MAD to the standard deviation of a normal distribution
+const MODIFIED_Z_SCALE: f64 = 0.6745;
+/// Scales the mean absolute deviation the same way, used when the MAD is 0
+const MEAN_AD_SCALE: f64 = 1.253314;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum OutlierMethod {
+    /// Beyond 1.5 (mild) or 3 (severe) IQRs from the quartiles
+    Tukey,
+    /// Modified z-score beyond 3.5 (mild) or 7 (severe)
+    ModifiedZScore,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, uniffi::Enum)]
+pub enum OutlierSeverity {
+    Mild,
+    Severe,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum OutlierSide {
+    Low,
+    High,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Outlier {
+    /// Index into the original samples
+    pub index: u32,
+    pub value: f64,
+    pub severity: OutlierSeverity,
+    pub side: OutlierSide,
+}
+
+/// Value thresholds past which a sample is an outlier
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Fences {
+    pub low_severe: f64,
+    pub low_mild: f64,
+    pub high_mild: f64,
+    pub high_severe: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct OutlierReport {
+    /// Samples without the dropped outliers, in original order
+    pub cleaned: Vec<f64>,
+    /// Every outlier found, dropped or not
+    pub outliers: Vec<Outlier>,
+    pub fences: Fences,
+    pub low_severe: u32,
+    pub low_mild: u32,
+    pub high_mild: u32,
+    pub high_severe: u32,
+}
+
+/// Classifies outliers in `samples`, dropping those at least as severe as `drop`
+#[uniffi::export]
+pub fn classify_outliers(
+    samples: Vec<f64>,
+    method: OutlierMethod,
+    drop: OutlierSeverity,
+) -> Result<OutlierReport, MetricsError> {
+    if samples.is_empty() {
+        return Err(MetricsError::InvalidArgument {
+            reason: "no samples to classify".to_string(),
+        });
+    }
+
+    let sorted = stats::sorted(&samples);
+    let fences = match method {
+        OutlierMethod::Tukey => {
+            let q1 = stats::percentile(&sorted, 25.0);
+            let q3 = stats::percentile(&sorted, 75.0);
+            let iqr = q3 - q1;
+            Fences {
+                low_severe: q1 - 3.0 * iqr,
+                low_mild: q1 - 1.5 * iqr,
+                high_mild: q3 + 1.5 * iqr,
+                high_severe: q3 + 3.0 * iqr,
+            }
+        }
+        OutlierMethod::ModifiedZScore => {
+            let median = stats::percentile(&sorted, 50.0);
+            let mad = stats::mad(&sorted);
+            // over half the samples equal the median, e.g. with a coarse timer
+            let unit = if mad > 0.0 {
+                mad / MODIFIED_Z_SCALE
+            } else {
+                let mean_ad = stats::mean(
+                    &samples
+                        .iter()
+                        .map(|x| (x - median).abs())
+                        .collect::<Vec<_>>(),
+                );
+                MEAN_AD_SCALE * mean_ad
+            };
+            Fences {
+                low_severe: median - MODIFIED_Z_SEVERE * unit,
+                low_mild: median - MODIFIED_Z_MILD * unit,
+                high_mild: median + MODIFIED_Z_MILD * unit,
+                high_severe: median + MODIFIED_Z_SEVERE * unit,
+            }
+        }
+    };
+
+    let mut report = OutlierReport {
+        cleaned: Vec::with_capacity(samples.len()),
+        outliers: vec![],
+        fences,
+        low_severe: 0,
+        low_mild: 0,
+        high_mild: 0,
+        high_severe: 0,
+    };
+
+    for (i, &value) in samples.iter().enumerate() {
+        let Some((severity, side)) = fences.classify(value) else {
+            report.cleaned.push(value);
+            continue;
+        };
+
+        *match (side, severity) {
+            (OutlierSide::Low, OutlierSeverity::Severe) => &mut report.low_severe,
+            (OutlierSide::Low, OutlierSeverity::Mild) => &mut report.low_mild,
+            (OutlierSide::High, OutlierSeverity::Mild) => &mut report.high_mild,
+            (OutlierSide::High, OutlierSeverity::Severe) => &mut report.high_severe,
+        } += 1;
+
+        if severity < drop {
+            report.cleaned.push(value);
+        }
+        report.outliers.push(Outlier {
+            index: i as u32,
+            value,
+            severity,
+            side,
+        });
+    }
+
+    Ok(report)
+}
+
+impl Fences {
+    fn classify(&self, value: f64) -> Option<(OutlierSeverity, OutlierSide)> {
+        if value < self.low_severe {
+            Some((OutlierSeverity::Severe, OutlierSide::Low))
+        } else if value < self.low_mild {
+            Some((OutlierSeverity::Mild, OutlierSide::Low))
+        } else if value > self.high_severe {
+            Some((OutlierSeverity::Severe, OutlierSide::High))
+        } else if value > self.high_mild {
+            Some((OutlierSeverity::Mild, OutlierSide::High))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(samples: &[f64], method: OutlierMethod) -> OutlierReport {
+        classify_outliers(samples.to_vec(), method, OutlierSeverity::Mild).unwrap()
+    }
+
+    #[test]
+    fn flags_far_values() {
+        let samples = [10.0, 10.2, 9.9, 10.1, 10.0, 9.8, 10.3, 50.0];
+        for method in [OutlierMethod::Tukey, OutlierMethod::ModifiedZScore] {
+            let report = classify(&samples, method);
+            assert_eq!(report.outliers.len(), 1, "{:?}", method);
+            assert_eq!(report.outliers[0].index, 7);
+            assert_eq!(report.outliers[0].severity, OutlierSeverity::Severe);
+            assert_eq!(report.cleaned.len(), 7);
+        }
+    }
+
+    #[test]
+    fn modified_z_score_with_zero_mad() {
+        let report = classify(
+            &[10.0, 10.0, 10.0, 10.0, 10.0, 10.1, 11.0],
+            OutlierMethod::ModifiedZScore,
+        );
+        assert!(report.cleaned.contains(&10.1));
+        assert!(report.fences.high_mild > 10.1);
+    }
+
+    #[test]
+    fn identical_samples_have_no_outliers() {
+        for method in [OutlierMethod::Tukey, OutlierMethod::ModifiedZScore] {
+            let report = classify(&[5.0; 10], method);
+            assert!(report.outliers.is_empty());
+            assert_eq!(report.cleaned.len(), 10);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{thread, time::Instant};
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::{MetricsError, blocking};
+
+/// CPU usage of a single process, in percent of one core
+///
+/// `user` and `system` are only available where `/proc` is (Linux & Android).
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct ProcessCpu {
+    pub pid: u32,
+    pub usage: f32,
+    pub user: Option<f32>,
+    pub system: Option<f32>,
+}
+
+/// CPU usage of `pid`, or of the current app process if not given
+///
+/// Blocks for `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL` to measure the delta.
+#[uniffi::export]
+pub fn get_process_cpu(pid: Option<u32>) -> Result<ProcessCpu, MetricsError> {
+    let mut sys = System::new();
+    let mut tracker = ProcessCpuTracker::new(pid);
+    tracker.sample(&mut sys)?;
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    tracker.sample(&mut sys)
+}
+
+/// Non-blocking [`get_process_cpu`], measured on a background thread
+#[uniffi::export]
+pub async fn get_process_cpu_async(pid: Option<u32>) -> Result<ProcessCpu, MetricsError> {
+    blocking::spawn(move || get_process_cpu(pid)).await
+}
+
+/// Keeps the previous CPU times of a process so usage can be
+/// computed as a delta between consecutive samples
+#[derive(Debug)]
+pub(crate) struct ProcessCpuTracker {
+    pid: Pid,
+    last: Option<(Instant, f64, f64)>,
+}
+
+impl ProcessCpuTracker {
+    pub(crate) fn new(pid: Option<u32>) -> Self {
+        Self {
+            pid: Pid::from_u32(pid.unwrap_or_else(std::process::id)),
+            last: None,
+        }
+    }
+
+    /// Refreshes only this process on `sys`
+    pub(crate) fn sample(&mut self, sys: &mut System) -> Result<ProcessCpu, MetricsError> {
+        sys.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[self.pid]),
+            true,
+            ProcessRefreshKind::nothing().with_cpu(),
+        );
+        let usage = sys
+            .process(self.pid)
+            .ok_or(MetricsError::ProcessNotFound {
+                pid: self.pid.as_u32(),
+            })?
+            .cpu_usage();
+
+        let (user, system) = self.split().unzip();
+        Ok(ProcessCpu {
+            pid: self.pid.as_u32(),
+            usage,
+            user,
+            system,
+        })
+    }
+
+    fn split(&mut self) -> Option<(f32, f32)> {
+        let (user, system) = cpu_times(self.pid)?;
+        let now = Instant::now();
+        let (at, last_user, last_system) = self.last.replace((now, user, system))?;
+
+        let elapsed = now.duration_since(at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+
+        let percent = |secs: f64| (secs.max(0.0) / elapsed * 100.0) as f32;
+        Some((percent(user - last_user), percent(system - last_system)))
+    }
+}
+
+/// Total user & system CPU time of `pid` in seconds
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn cpu_times(pid: Pid) -> Option<(f64, f64)> {
+    let stat = crate::procfs::read_stat(format!("/proc/{}/stat", pid)).ok()?;
+    let hz = crate::procfs::clock_ticks();
+    Some((stat.utime as f64 / hz, stat.stime as f64 / hz))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn cpu_times(_pid: Pid) -> Option<(f64, f64)> {
+    None
+}
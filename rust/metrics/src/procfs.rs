@@ -0,0 +1,135 @@
+use std::{fs, path::Path};
+
+use crate::MetricsError;
+
+/// Fields of interest from `/proc/<pid>/stat`
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Stat {
+    /// Executable or thread name, truncated to 15 bytes by the kernel
+    pub(crate) comm: String,
+    /// Faults that did not need to load a page from disk
+    pub(crate) minflt: u64,
+    pub(crate) majflt: u64,
+    /// Clock ticks spent in user mode
+    pub(crate) utime: u64,
+    /// Clock ticks spent in kernel mode
+    pub(crate) stime: u64,
+    pub(crate) num_threads: u64,
+}
+
+pub(crate) fn read_stat<P: AsRef<Path>>(path: P) -> Result<Stat, MetricsError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    parse_stat(&content).ok_or_else(|| MetricsError::Io {
+        reason: format!("malformed {}", path.display()),
+    })
+}
+
+/// `comm` may contain spaces and parentheses, so fields are
+/// counted from the last `)` onwards, starting at `state` (field 3).
+fn parse_stat(content: &str) -> Option<Stat> {
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let rest = &content[close + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+
+    Some(Stat {
+        comm: content.get(open + 1..close)?.to_string(),
+        minflt: field(10)?,
+        majflt: field(12)?,
+        utime: field(14)?,
+        stime: field(15)?,
+        num_threads: field(20)?,
+    })
+}
+
+/// Fields of interest from `/proc/<pid>/status`
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Status {
+    pub(crate) voluntary_ctxt_switches: u64,
+    pub(crate) nonvoluntary_ctxt_switches: u64,
+}
+
+pub(crate) fn read_status<P: AsRef<Path>>(path: P) -> Result<Status, MetricsError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    parse_status(&content).ok_or_else(|| MetricsError::Io {
+        reason: format!("malformed {}", path.display()),
+    })
+}
+
+/// `Key:\tvalue` lines
+fn parse_status(content: &str) -> Option<Status> {
+    let field = |key: &str| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))?
+            .trim()
+            .parse::<u64>()
+            .ok()
+    };
+
+    Some(Status {
+        voluntary_ctxt_switches: field("voluntary_ctxt_switches")?,
+        nonvoluntary_ctxt_switches: field("nonvoluntary_ctxt_switches")?,
+    })
+}
+
+/// Number of entries in a `/proc/<pid>/fd` directory
+pub(crate) fn count_fds<P: AsRef<Path>>(path: P) -> Result<u32, MetricsError> {
+    Ok(fs::read_dir(path)?.count() as u32)
+}
+
+/// Clock ticks per second used by the `stat` time fields
+pub(crate) fn clock_ticks() -> f64 {
+    // SAFETY: sysconf has no preconditions
+    let hz = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if hz > 0 { hz as f64 } else { 100.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT: &str =
+        "4242 (a) (b c) R 1 4242 4242 0 -1 4194560 1500 0 7 0 321 45 0 0 20 0 9 0 12345 0";
+
+    #[test]
+    fn parses_stat_with_awkward_comm() {
+        let stat = parse_stat(STAT).unwrap();
+        assert_eq!(stat.comm, "a) (b c");
+        assert_eq!((stat.utime, stat.stime), (321, 45));
+        assert_eq!((stat.minflt, stat.majflt), (1500, 7));
+        assert_eq!(stat.num_threads, 9);
+    }
+
+    #[test]
+    fn parses_status() {
+        let status = parse_status(
+            "Name:\tmqt_js\nThreads:\t9\nvoluntary_ctxt_switches:\t1234\n\
+             nonvoluntary_ctxt_switches:\t56\n",
+        )
+        .unwrap();
+        assert_eq!(status.voluntary_ctxt_switches, 1234);
+        assert_eq!(status.nonvoluntary_ctxt_switches, 56);
+
+        assert!(parse_status("voluntary_ctxt_switches:\t1\n").is_none());
+        assert!(
+            parse_status("voluntary_ctxt_switches:\tx\nnonvoluntary_ctxt_switches:\t1").is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_stat() {
+        assert!(parse_stat("4242 (a) R 1 2 3").is_none());
+        assert!(parse_stat("4242 a R 1 2 3").is_none());
+    }
+
+    #[test]
+    fn reads_own_stat() {
+        let stat = read_stat("/proc/self/stat").unwrap();
+        assert!(!stat.comm.is_empty());
+        assert!(read_stat("/proc/self/nope").is_err());
+    }
+}
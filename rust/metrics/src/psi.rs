@@ -0,0 +1,237 @@
+use std::{fs, path::Path};
+
+use crate::Sample;
+
+const PRESSURE_ROOT: &str = "/proc/pressure";
+
+/// One `some` or `full` line of a PSI file
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct PressureStall {
+    /// Percent of time stalled over the last 10, 60 and 300 seconds
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    /// Total stall time in microseconds
+    pub total: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct ResourcePressure {
+    /// Some tasks stalled on the resource
+    pub some: PressureStall,
+    /// All non-idle tasks stalled at once, missing for CPU on older kernels
+    pub full: Option<PressureStall>,
+}
+
+/// Pressure Stall Information, `None` where the kernel does not expose it
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct Pressure {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+/// Microseconds stalled between two readings
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct PressureDelta {
+    pub cpu_some: Option<u64>,
+    pub cpu_full: Option<u64>,
+    pub memory_some: Option<u64>,
+    pub memory_full: Option<u64>,
+    pub io_some: Option<u64>,
+    pub io_full: Option<u64>,
+}
+
+#[uniffi::export]
+pub fn get_pressure() -> Pressure {
+    read_pressure(Path::new(PRESSURE_ROOT))
+}
+
+#[uniffi::export]
+pub fn pressure_delta(start: Pressure, end: Pressure) -> PressureDelta {
+    let some = |s: Option<ResourcePressure>, e: Option<ResourcePressure>| {
+        Some(e?.some.total.saturating_sub(s?.some.total))
+    };
+    let full = |s: Option<ResourcePressure>, e: Option<ResourcePressure>| {
+        Some(e?.full?.total.saturating_sub(s?.full?.total))
+    };
+
+    PressureDelta {
+        cpu_some: some(start.cpu, end.cpu),
+        cpu_full: full(start.cpu, end.cpu),
+        memory_some: some(start.memory, end.memory),
+        memory_full: full(start.memory, end.memory),
+        io_some: some(start.io, end.io),
+        io_full: full(start.io, end.io),
+    }
+}
+
+/// [`pressure_delta`] between the first and last samples with pressure
+#[uniffi::export]
+pub fn session_pressure(samples: Vec<Sample>) -> Option<PressureDelta> {
+    let mut readings = samples.iter().filter_map(|s| s.pressure);
+    let start = readings.next()?;
+    let end = readings.next_back()?;
+    Some(pressure_delta(start, end))
+}
+
+pub(crate) fn read_pressure(root: &Path) -> Pressure {
+    let read = |name: &str| parse_resource(&fs::read_to_string(root.join(name)).ok()?);
+    Pressure {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    }
+}
+
+fn parse_resource(content: &str) -> Option<ResourcePressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("some") => some = parse_stall(fields),
+            Some("full") => full = parse_stall(fields),
+            _ => {}
+        }
+    }
+
+    Some(ResourcePressure { some: some?, full })
+}
+
+/// Parses `avg10=0.00 avg60=0.00 avg300=0.00 total=0`
+fn parse_stall<'a>(fields: impl Iterator<Item = &'a str>) -> Option<PressureStall> {
+    let mut stall = PressureStall {
+        avg10: 0.0,
+        avg60: 0.0,
+        avg300: 0.0,
+        total: 0,
+    };
+    for field in fields {
+        let (key, value) = field.split_once('=')?;
+        match key {
+            "avg10" => stall.avg10 = value.parse().ok()?,
+            "avg60" => stall.avg60 = value.parse().ok()?,
+            "avg300" => stall.avg300 = value.parse().ok()?,
+            "total" => stall.total = value.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(stall)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FakeSysfs;
+
+    const CPU: &str = "some avg10=1.64 avg60=2.14 avg300=2.86 total=43933419";
+
+    fn stall(total: u64) -> PressureStall {
+        PressureStall {
+            avg10: 0.0,
+            avg60: 0.0,
+            avg300: 0.0,
+            total,
+        }
+    }
+
+    fn pressure(cpu_some: u64, io_some: u64, io_full: u64) -> Pressure {
+        Pressure {
+            cpu: Some(ResourcePressure {
+                some: stall(cpu_some),
+                full: None,
+            }),
+            memory: None,
+            io: Some(ResourcePressure {
+                some: stall(io_some),
+                full: Some(stall(io_full)),
+            }),
+        }
+    }
+
+    #[test]
+    fn parses_pressure_files() {
+        let fake = FakeSysfs::new();
+        fake.write("cpu", CPU).write(
+            "io",
+            "some avg10=0.00 avg60=0.10 avg300=0.05 total=3610367\n\
+             full avg10=0.50 avg60=0.00 avg300=0.02 total=3149032",
+        );
+
+        let p = read_pressure(&fake.root);
+        let cpu = p.cpu.unwrap();
+        assert_eq!(
+            cpu.some,
+            PressureStall {
+                avg10: 1.64,
+                avg60: 2.14,
+                avg300: 2.86,
+                total: 43_933_419,
+            }
+        );
+        // older kernels have no `full` line for CPU
+        assert_eq!(cpu.full, None);
+
+        let io = p.io.unwrap();
+        assert_eq!(io.some.total, 3_610_367);
+        assert_eq!(io.full.unwrap().avg10, 0.5);
+        assert_eq!(io.full.unwrap().total, 3_149_032);
+        assert_eq!(p.memory, None);
+    }
+
+    #[test]
+    fn skips_malformed_files() {
+        let fake = FakeSysfs::new();
+        fake.write("cpu", "some avg10=x avg60=0 avg300=0 total=0")
+            .write("memory", "full avg10=0.00 avg60=0.00 avg300=0.00 total=5")
+            .write("io", "some avg10 total=1");
+
+        let p = read_pressure(&fake.root);
+        assert_eq!((p.cpu, p.memory, p.io), (None, None, None));
+    }
+
+    #[test]
+    fn deltas_between_readings() {
+        let d = pressure_delta(pressure(100, 50, 10), pressure(400, 50, 30));
+        assert_eq!(d.cpu_some, Some(300));
+        assert_eq!(d.cpu_full, None);
+        assert_eq!((d.memory_some, d.memory_full), (None, None));
+        assert_eq!((d.io_some, d.io_full), (Some(0), Some(20)));
+
+        // counters never go backwards, but a reset must not underflow
+        assert_eq!(
+            pressure_delta(pressure(400, 0, 0), pressure(100, 0, 0)).cpu_some,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn session_delta_spans_first_and_last_readings() {
+        let sample = |timestamp: f64, pressure: Option<Pressure>| Sample {
+            timestamp,
+            cpu: None,
+            cores: None,
+            memory: None,
+            process: None,
+            battery: None,
+            pressure,
+        };
+
+        let samples = vec![
+            sample(0.0, None),
+            sample(1.0, Some(pressure(100, 0, 0))),
+            sample(2.0, Some(pressure(150, 0, 0))),
+            sample(3.0, Some(pressure(700, 5, 1))),
+            sample(4.0, None),
+        ];
+        let d = session_pressure(samples.clone()).unwrap();
+        assert_eq!(
+            (d.cpu_some, d.io_some, d.io_full),
+            (Some(600), Some(5), Some(1))
+        );
+
+        assert_eq!(session_pressure(samples[..2].to_vec()), None);
+        assert_eq!(session_pressure(vec![]), None);
+    }
+}
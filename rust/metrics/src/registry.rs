@@ -0,0 +1,151 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use crate::Histogram;
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    counters: BTreeMap::new(),
+    gauges: BTreeMap::new(),
+    histograms: BTreeMap::new(),
+});
+
+/// Name and sorted labels, so the same series always maps to one entry
+pub(crate) type Key = (String, Vec<(String, String)>);
+
+#[derive(Debug)]
+struct Registry {
+    counters: BTreeMap<Key, u64>,
+    gauges: BTreeMap<Key, f64>,
+    histograms: BTreeMap<Key, Arc<Histogram>>,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct CounterValue {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    pub value: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct GaugeValue {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    pub value: f64,
+}
+
+/// Every counter and gauge, sorted by name then labels
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct RegistrySnapshot {
+    pub counters: Vec<CounterValue>,
+    pub gauges: Vec<GaugeValue>,
+}
+
+/// Increments a monotonic counter by `by`, creating it at 0 if needed
+#[uniffi::export]
+pub fn counter_inc(name: String, labels: Option<HashMap<String, String>>, by: u64) {
+    let mut registry = REGISTRY.lock().unwrap();
+    let value = registry.counters.entry(key(name, labels)).or_default();
+    *value = value.saturating_add(by);
+}
+
+#[uniffi::export]
+pub fn gauge_set(name: String, labels: Option<HashMap<String, String>>, value: f64) {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .gauges
+        .insert(key(name, labels), value);
+}
+
+/// Adds `by` to a gauge, creating it at 0 if needed
+#[uniffi::export]
+pub fn gauge_inc(name: String, labels: Option<HashMap<String, String>>, by: f64) {
+    *REGISTRY
+        .lock()
+        .unwrap()
+        .gauges
+        .entry(key(name, labels))
+        .or_default() += by;
+}
+
+/// Subtracts `by` from a gauge, creating it at 0 if needed
+#[uniffi::export]
+pub fn gauge_dec(name: String, labels: Option<HashMap<String, String>>, by: f64) {
+    gauge_inc(name, labels, -by);
+}
+
+/// Exposes `histogram` under `name`, replacing any histogram already there
+#[uniffi::export]
+pub fn register_histogram(
+    name: String,
+    labels: Option<HashMap<String, String>>,
+    histogram: Arc<Histogram>,
+) {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .histograms
+        .insert(key(name, labels), histogram);
+}
+
+#[uniffi::export]
+pub fn unregister_histogram(name: String, labels: Option<HashMap<String, String>>) {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .histograms
+        .remove(&key(name, labels));
+}
+
+#[uniffi::export]
+pub fn registry_snapshot() -> RegistrySnapshot {
+    let registry = REGISTRY.lock().unwrap();
+    RegistrySnapshot {
+        counters: registry
+            .counters
+            .iter()
+            .map(|((name, labels), value)| CounterValue {
+                name: name.clone(),
+                labels: labels.iter().cloned().collect(),
+                value: *value,
+            })
+            .collect(),
+        gauges: registry
+            .gauges
+            .iter()
+            .map(|((name, labels), value)| GaugeValue {
+                name: name.clone(),
+                labels: labels.iter().cloned().collect(),
+                value: *value,
+            })
+            .collect(),
+    }
+}
+
+/// Registered histograms, sorted by name then labels
+pub(crate) fn registered_histograms() -> Vec<(Key, Arc<Histogram>)> {
+    REGISTRY
+        .lock()
+        .unwrap()
+        .histograms
+        .iter()
+        .map(|(key, h)| (key.clone(), h.clone()))
+        .collect()
+}
+
+/// Removes every counter, gauge and registered histogram
+#[uniffi::export]
+pub fn registry_reset() {
+    let mut registry = REGISTRY.lock().unwrap();
+    registry.counters.clear();
+    registry.gauges.clear();
+    registry.histograms.clear();
+}
+
+fn key(name: String, labels: Option<HashMap<String, String>>) -> Key {
+    let mut labels: Vec<(String, String)> = labels.unwrap_or_default().into_iter().collect();
+    labels.sort();
+    (name, labels)
+}
@@ -0,0 +1,143 @@
+use std::sync::Mutex;
+
+use sysinfo::{CpuRefreshKind, System};
+
+use crate::{
+    Battery, CpuCore, Memory, MetricsError, Pressure, ProcessCpu, battery, clock, cpu,
+    get_pressure, memory, process::ProcessCpuTracker, sysfs::Sysfs,
+};
+
+/// Which subsystems a [`Sampler`] should refresh
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct MetricKinds {
+    #[uniffi(default = true)]
+    pub cpu: bool,
+    #[uniffi(default = false)]
+    pub cores: bool,
+    #[uniffi(default = false)]
+    pub memory: bool,
+    #[uniffi(default = false)]
+    pub process: bool,
+    #[uniffi(default = false)]
+    pub battery: bool,
+    #[uniffi(default = false)]
+    pub pressure: bool,
+}
+
+impl Default for MetricKinds {
+    fn default() -> Self {
+        Self {
+            cpu: true,
+            cores: false,
+            memory: false,
+            process: false,
+            battery: false,
+            pressure: false,
+        }
+    }
+}
+
+/// A single reading, with only the requested kinds filled in
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct Sample {
+    /// Milliseconds on the crate's monotonic clock
+    pub timestamp: f64,
+    pub cpu: Option<f32>,
+    pub cores: Option<Vec<CpuCore>>,
+    pub memory: Option<Memory>,
+    pub process: Option<ProcessCpu>,
+    pub battery: Option<Battery>,
+    pub pressure: Option<Pressure>,
+}
+
+/// Long-lived sampler reusing one `System` between calls
+///
+/// CPU values are deltas since the previous call (or since construction),
+/// so calls should be at least `sysinfo::MINIMUM_CPU_UPDATE_INTERVAL` apart
+/// for accurate readings.
+#[derive(Debug, uniffi::Object)]
+pub struct Sampler {
+    inner: Mutex<SamplerInner>,
+}
+
+#[derive(Debug)]
+struct SamplerInner {
+    sys: System,
+    process: ProcessCpuTracker,
+}
+
+#[uniffi::export]
+impl Sampler {
+    /// Samples `pid` for process CPU, or the current app process if not given
+    #[uniffi::constructor]
+    pub fn new(pid: Option<u32>) -> Self {
+        let mut sys = System::new();
+        sys.refresh_cpu_specifics(CpuRefreshKind::nothing().with_cpu_usage());
+
+        let mut process = ProcessCpuTracker::new(pid);
+        let _ = process.sample(&mut sys);
+
+        Self {
+            inner: Mutex::new(SamplerInner { sys, process }),
+        }
+    }
+
+    pub fn sample(&self, kinds: MetricKinds) -> Result<Sample, MetricsError> {
+        let mut inner = self.inner.lock().unwrap();
+        let SamplerInner { sys, process } = &mut *inner;
+
+        let cores = kinds.cores.then(|| cpu::read_cores(sys));
+        let cpu = kinds.cpu.then(|| {
+            if cores.is_none() {
+                sys.refresh_cpu_usage();
+            }
+            sys.global_cpu_usage()
+        });
+        // refreshing the process for memory also resets its CPU delta, so read CPU first
+        let process = kinds.process.then(|| process.sample(sys)).transpose()?;
+
+        Ok(Sample {
+            timestamp: clock::now_ms(),
+            cpu,
+            cores,
+            memory: kinds.memory.then(|| memory::read_memory(sys)),
+            process,
+            battery: kinds
+                .battery
+                .then(|| battery::read_battery(&Sysfs::new(None)))
+                .flatten(),
+            pressure: kinds.pressure.then(get_pressure),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::threads::Spinner;
+
+    #[test]
+    fn process_cpu_survives_memory_refresh() {
+        let _spinner = Spinner::start("rnbench-spin");
+
+        let sampler = Sampler::new(None);
+        let kinds = MetricKinds {
+            cpu: false,
+            memory: true,
+            process: true,
+            ..MetricKinds::default()
+        };
+        // sysinfo skips the first delta of a process that had no CPU time yet
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sampler.sample(kinds).unwrap();
+        for _ in 0..3 {
+            thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL * 2);
+            let sample = sampler.sample(kinds).unwrap();
+            assert!(sample.memory.unwrap().process_resident > 0);
+            let usage = sample.process.unwrap().usage;
+            assert!(usage > 20.0, "usage {}", usage);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{MetricKinds, MetricsError, Sampler, Session, render_openmetrics};
+
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+/// Bounds how long a silent or stalled client can hold a handler thread
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Request line and headers beyond this are ignored
+const MAX_REQUEST_HEAD: u64 = 8 * 1024;
+const OPENMETRICS_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct ServerConfig {
+    /// Port on localhost, 0 picks a free one
+    #[uniffi(default = 9464)]
+    pub port: u16,
+    /// Interval between samples of the `/events` stream
+    #[uniffi(default = 1000)]
+    pub interval_ms: u32,
+    /// Kinds served by `/metrics` and `/events`, defaults to all of them
+    #[uniffi(default = None)]
+    pub kinds: Option<MetricKinds>,
+}
+
+/// Local HTTP server exposing live metrics
+///
+/// - `/metrics`: OpenMetrics snapshot
+/// - `/session`: timeline of the attached session as JSON
+/// - `/events`: Server-Sent-Events stream of samples
+#[derive(Debug, uniffi::Object)]
+pub struct MetricsServer {
+    port: u16,
+    running: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Serves on `127.0.0.1`, reachable from a laptop with `adb forward`
+#[uniffi::export]
+pub fn start_server(
+    config: ServerConfig,
+    session: Option<Arc<Session>>,
+) -> Result<Arc<MetricsServer>, MetricsError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+
+    let running = Arc::new(AtomicBool::new(true));
+    let state = Arc::new(State {
+        interval: Duration::from_millis(config.interval_ms.max(1) as u64),
+        kinds: config.kinds.unwrap_or(MetricKinds {
+            cpu: true,
+            cores: true,
+            memory: true,
+            process: true,
+            battery: true,
+            pressure: true,
+        }),
+        sampler: Sampler::new(None),
+        session,
+        running: running.clone(),
+    });
+
+    let handle = thread::spawn(move || {
+        while state.running.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let state = state.clone();
+                    thread::spawn(move || {
+                        let _ = handle(stream, &state);
+                    });
+                }
+                // nonblocking, so also `WouldBlock` when nobody is connecting
+                Err(_) => thread::sleep(ACCEPT_POLL),
+            }
+        }
+    });
+
+    Ok(Arc::new(MetricsServer {
+        port,
+        running,
+        handle: Mutex::new(Some(handle)),
+    }))
+}
+
+#[uniffi::export]
+impl MetricsServer {
+    /// Port actually bound
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting connections and ends open event streams
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    interval: Duration,
+    kinds: MetricKinds,
+    /// Shared by `/metrics` scrapes, so CPU is the delta since the last scrape
+    sampler: Sampler,
+    session: Option<Arc<Session>>,
+    running: Arc<AtomicBool>,
+}
+
+fn handle(mut stream: TcpStream, state: &State) -> Result<(), MetricsError> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_HEAD));
+
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // headers are not needed, but must be consumed before responding
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    if method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", "");
+    }
+
+    match path {
+        "/metrics" => {
+            let body = render_openmetrics(Some(state.sampler.sample(state.kinds)?));
+            respond(&mut stream, "200 OK", OPENMETRICS_TYPE, &body)
+        }
+        "/session" => match &state.session {
+            Some(session) => {
+                let body = serde_json::to_string(&session.samples())?;
+                respond(&mut stream, "200 OK", "application/json", &body)
+            }
+            None => respond(
+                &mut stream,
+                "404 Not Found",
+                "text/plain",
+                "no session attached",
+            ),
+        },
+        "/events" => stream_events(&mut stream, state),
+        _ => respond(&mut stream, "404 Not Found", "text/plain", ""),
+    }
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<(), MetricsError> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(stream.flush()?)
+}
+
+/// Streams samples until the client disconnects or the server stops
+fn stream_events(stream: &mut TcpStream, state: &State) -> Result<(), MetricsError> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+    )?;
+    stream.flush()?;
+
+    // own sampler so the stream's CPU deltas follow its own interval
+    let sampler = Sampler::new(None);
+    while state.running.load(Ordering::SeqCst) {
+        thread::sleep(state.interval);
+        let sample = serde_json::to_string(&sampler.sample(state.kinds)?)?;
+        write!(stream, "data: {}\n\n", sample)?;
+        stream.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::start_session;
+
+    fn start(session: Option<Arc<Session>>) -> Arc<MetricsServer> {
+        let config = ServerConfig {
+            port: 0,
+            interval_ms: 50,
+            kinds: Some(MetricKinds::default()),
+        };
+        start_server(config, session).unwrap()
+    }
+
+    fn connect(server: &MetricsServer) -> TcpStream {
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+        stream.set_read_timeout(Some(IO_TIMEOUT)).unwrap();
+        stream
+    }
+
+    /// Status line, headers and body of a complete response
+    fn request(server: &MetricsServer, method: &str, path: &str) -> (String, String) {
+        let mut stream = connect(server);
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            method, path
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+
+    #[test]
+    fn serves_openmetrics() {
+        let server = start(None);
+        let (head, body) = request(&server, "GET", "/metrics?x=1");
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains(&format!("Content-Type: {}\r\n", OPENMETRICS_TYPE)));
+        assert!(body.contains("# TYPE rnbench_cpu_usage_percent gauge\n"));
+        assert!(body.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn serves_attached_session() {
+        let server = start(None);
+        let (head, _) = request(&server, "GET", "/session");
+        assert!(head.starts_with("HTTP/1.1 404 "));
+
+        let session = start_session(10, MetricKinds::default());
+        thread::sleep(Duration::from_millis(50));
+        let server = start(Some(session.clone()));
+        let (head, body) = request(&server, "GET", "/session");
+        session.stop();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        let samples: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert!(!samples.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_requests() {
+        let server = start(None);
+        assert!(
+            request(&server, "POST", "/metrics")
+                .0
+                .starts_with("HTTP/1.1 405 ")
+        );
+        assert!(
+            request(&server, "GET", "/nope")
+                .0
+                .starts_with("HTTP/1.1 404 ")
+        );
+    }
+
+    #[test]
+    fn stop_ends_event_streams() {
+        let server = start(None);
+        let mut stream = connect(&server);
+        write!(stream, "GET /events HTTP/1.1\r\n\r\n").unwrap();
+
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        while !line.starts_with("data: ") {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+        }
+        let sample: serde_json::Value = serde_json::from_str(&line[6..]).unwrap();
+        assert!(sample["cpu"].is_number());
+
+        server.stop();
+        assert!(!server.is_running());
+        let started = Instant::now();
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert!(started.elapsed() < IO_TIMEOUT);
+    }
+
+    #[test]
+    fn caps_request_head() {
+        let server = start(None);
+        let mut stream = connect(&server);
+        let started = Instant::now();
+        // an endless header line, never terminated
+        let _ = stream
+            .write_all(format!("GET /nope HTTP/1.1\r\nX: {}", "a".repeat(64 * 1024)).as_bytes());
+        let mut response = Vec::new();
+        let _ = stream.read_to_end(&mut response);
+        assert!(started.elapsed() < IO_TIMEOUT);
+    }
+}
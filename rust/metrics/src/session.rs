@@ -0,0 +1,77 @@
+use std::sync::{
+    Arc, Mutex,
+    mpsc::{self, RecvTimeoutError, Sender},
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{MetricKinds, Sample, Sampler};
+
+/// Background sampling of the requested metrics at a fixed interval
+#[derive(Debug, uniffi::Object)]
+pub struct Session {
+    tx: Mutex<Option<Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    samples: Arc<Mutex<Vec<Sample>>>,
+}
+
+/// Starts sampling `kinds` every `interval_ms` on a background thread
+#[uniffi::export]
+pub fn start_session(interval_ms: u32, kinds: MetricKinds) -> Arc<Session> {
+    let (tx, rx) = mpsc::channel::<()>();
+    let samples = Arc::new(Mutex::new(Vec::new()));
+    let interval = Duration::from_millis(interval_ms.max(1) as u64);
+
+    let buffer = samples.clone();
+    let handle = thread::spawn(move || {
+        let sampler = Sampler::new(None);
+        let mut next = Instant::now() + interval;
+
+        // deadline based so slow samples do not drift the timeline,
+        // stops once the sender is dropped
+        while let Err(RecvTimeoutError::Timeout) =
+            rx.recv_timeout(next.saturating_duration_since(Instant::now()))
+        {
+            if let Ok(sample) = sampler.sample(kinds) {
+                buffer.lock().unwrap().push(sample);
+            }
+            next += interval;
+        }
+    });
+
+    Arc::new(Session {
+        tx: Mutex::new(Some(tx)),
+        handle: Mutex::new(Some(handle)),
+        samples,
+    })
+}
+
+#[uniffi::export]
+impl Session {
+    pub fn is_running(&self) -> bool {
+        self.tx.lock().unwrap().is_some()
+    }
+
+    /// Samples collected so far, without stopping
+    pub fn samples(&self) -> Vec<Sample> {
+        self.samples.lock().unwrap().clone()
+    }
+
+    /// Stops sampling and returns the full timeline
+    ///
+    /// Calling this again returns the same timeline.
+    pub fn stop(&self) -> Vec<Sample> {
+        // dropping the sender wakes the sampling thread
+        drop(self.tx.lock().unwrap().take());
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        self.samples()
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
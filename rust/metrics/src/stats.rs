@@ -0,0 +1,436 @@
+use crate::MetricsError;
+
+const DEFAULT_PERCENTILES: [f64; 4] = [50.0, 90.0, 95.0, 99.0];
+const BOOTSTRAP_SEED: u64 = 0x5eed_cafe;
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct SummaryConfig {
+    /// Percentiles (0-100) to report, defaults to p50, p90, p95 and p99
+    #[uniffi(default = None)]
+    pub percentiles: Option<Vec<f64>>,
+    /// Confidence level of the mean's interval, in (0, 1)
+    #[uniffi(default = 0.95)]
+    pub confidence_level: f64,
+    #[uniffi(default = 1000)]
+    pub bootstrap_resamples: u32,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            percentiles: None,
+            confidence_level: 0.95,
+            bootstrap_resamples: 1000,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Percentile {
+    pub percentile: f64,
+    pub value: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct ConfidenceInterval {
+    pub level: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Summary {
+    pub count: u32,
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Only defined when every sample is positive
+    pub geometric_mean: Option<f64>,
+    pub stddev: f64,
+    /// Median absolute deviation from the median, unscaled
+    pub mad: f64,
+    pub percentiles: Vec<Percentile>,
+    /// Bootstrap percentile interval of the mean
+    pub mean_ci: ConfidenceInterval,
+}
+
+#[uniffi::export]
+pub fn summarize(samples: Vec<f64>, config: SummaryConfig) -> Result<Summary, MetricsError> {
+    if samples.is_empty() {
+        return Err(MetricsError::InvalidArgument {
+            reason: "no samples to summarize".to_string(),
+        });
+    }
+    if let Some(x) = samples.iter().find(|x| !x.is_finite()) {
+        return Err(MetricsError::InvalidArgument {
+            reason: format!("cannot summarize {}", x),
+        });
+    }
+    if !(config.confidence_level > 0.0 && config.confidence_level < 1.0) {
+        return Err(MetricsError::InvalidArgument {
+            reason: format!("confidence level {} not in (0, 1)", config.confidence_level),
+        });
+    }
+
+    let percentiles = config
+        .percentiles
+        .unwrap_or_else(|| DEFAULT_PERCENTILES.to_vec());
+    if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        return Err(MetricsError::InvalidArgument {
+            reason: format!("percentile {} not in [0, 100]", p),
+        });
+    }
+
+    let sorted = sorted(&samples);
+    Ok(Summary {
+        count: samples.len() as u32,
+        mean: mean(&samples),
+        median: percentile(&sorted, 50.0),
+        min: sorted[0],
+        max: sorted[sorted.len() - 1],
+        geometric_mean: geometric_mean(&samples),
+        stddev: stddev(&samples),
+        mad: mad(&sorted),
+        percentiles: percentiles
+            .into_iter()
+            .map(|p| Percentile {
+                percentile: p,
+                value: percentile(&sorted, p),
+            })
+            .collect(),
+        mean_ci: bootstrap_mean_ci(
+            &samples,
+            config.confidence_level,
+            config.bootstrap_resamples.max(1),
+        ),
+    })
+}
+
+/// Arithmetic mean, `NaN` when empty
+pub(crate) fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+/// Sample (n - 1) standard deviation
+pub(crate) fn stddev(samples: &[f64]) -> f64 {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+
+    let m = mean(samples);
+    let var = samples.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (samples.len() - 1) as f64;
+    var.sqrt()
+}
+
+/// Percentile `p` (0-100) of already sorted samples, linearly interpolated
+pub(crate) fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => f64::NAN,
+        1 => sorted[0],
+        n => {
+            let rank = (p.clamp(0.0, 100.0) / 100.0) * (n - 1) as f64;
+            let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+        }
+    }
+}
+
+pub(crate) fn sorted(samples: &[f64]) -> Vec<f64> {
+    let mut s = samples.to_vec();
+    s.sort_by(f64::total_cmp);
+    s
+}
+
+pub(crate) fn geometric_mean(samples: &[f64]) -> Option<f64> {
+    if samples.iter().any(|x| *x <= 0.0) {
+        return None;
+    }
+    Some((samples.iter().map(|x| x.ln()).sum::<f64>() / samples.len() as f64).exp())
+}
+
+/// Median absolute deviation of already sorted samples
+pub(crate) fn mad(sorted_samples: &[f64]) -> f64 {
+    let median = percentile(sorted_samples, 50.0);
+    let deviations: Vec<f64> = sorted_samples.iter().map(|x| (x - median).abs()).collect();
+    percentile(&sorted(&deviations), 50.0)
+}
+
+fn bootstrap_mean_ci(samples: &[f64], level: f64, resamples: u32) -> ConfidenceInterval {
+    let mut rng = Rng::new(BOOTSTRAP_SEED);
+    let n = samples.len();
+
+    let means: Vec<f64> = (0..resamples)
+        .map(|_| (0..n).map(|_| samples[rng.below(n)]).sum::<f64>() / n as f64)
+        .collect();
+    let means = sorted(&means);
+
+    let tail = (1.0 - level) / 2.0 * 100.0;
+    ConfidenceInterval {
+        level,
+        lower: percentile(&means, tail),
+        upper: percentile(&means, 100.0 - tail),
+    }
+}
+
+/// splitmix64, good enough for resampling and ids
+#[derive(Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Standard normal CDF
+pub(crate) fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// Complementary error function, fractional error below 1.2e-7
+fn erfc(x: f64) -> f64 {
+    const COEFFS: [f64; 10] = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ];
+
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = COEFFS.iter().rev().fold(0.0, |acc, c| acc * t + c);
+    let r = t * (-z * z + poly).exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+/// Two-sided p-value of Student's t distribution with `df` degrees of freedom
+pub(crate) fn student_t_two_sided(t: f64, df: f64) -> f64 {
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // the continued fraction converges fastest on this side
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_cf(a, b, x) / a
+    } else {
+        1.0 - front * beta_cf(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz)
+fn beta_cf(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITER: usize = 300;
+    const EPS: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let guard = |v: f64| if v.abs() < TINY { TINY } else { v };
+    let mut c = 1.0;
+    let mut d = 1.0 / guard(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+
+    for m in 1..=MAX_ITER {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        h *= d * c;
+
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 / guard(1.0 + aa * d);
+        c = guard(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPS {
+            break;
+        }
+    }
+    h
+}
+
+/// Lanczos approximation of ln Γ(x) for x > 0
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let series = COEFFS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |acc, (i, c)| {
+            acc + c / (x + 1.0 + i as f64)
+        });
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // reference values computed with mpmath at 30 digits
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn summarizes_exact_values() {
+        let s = summarize(vec![1.0, 2.0, 3.0, 4.0, 100.0], SummaryConfig::default()).unwrap();
+        assert_eq!(s.count, 5);
+        assert_eq!(s.mean, 22.0);
+        assert_eq!(s.median, 3.0);
+        assert_eq!(s.min, 1.0);
+        assert_eq!(s.max, 100.0);
+        assert_eq!(s.mad, 1.0);
+        assert_close(s.stddev, 1902.5f64.sqrt(), 1e-12);
+        assert_close(s.geometric_mean.unwrap(), 2400f64.powf(0.2), 1e-12);
+
+        let percentiles: Vec<(f64, f64)> = s
+            .percentiles
+            .iter()
+            .map(|p| (p.percentile, p.value))
+            .collect();
+        assert_eq!(percentiles.len(), 4);
+        assert_eq!(percentiles[0], (50.0, 3.0));
+        assert_close(percentiles[1].1, 61.6, 1e-12);
+        assert_close(percentiles[2].1, 80.8, 1e-12);
+        assert_close(percentiles[3].1, 96.16, 1e-12);
+    }
+
+    #[test]
+    fn interpolates_percentiles() {
+        let sorted = [10.0, 20.0, 30.0, 40.0];
+        assert_eq!(percentile(&sorted, 0.0), 10.0);
+        assert_eq!(percentile(&sorted, 50.0), 25.0);
+        assert_eq!(percentile(&sorted, 100.0), 40.0);
+        assert_eq!(percentile(&sorted, 150.0), 40.0);
+        assert_eq!(percentile(&[7.0], 90.0), 7.0);
+        assert!(percentile(&[], 50.0).is_nan());
+    }
+
+    #[test]
+    fn geometric_mean_needs_positive_samples() {
+        assert_eq!(geometric_mean(&[1.0, 0.0, 4.0]), None);
+        assert_eq!(geometric_mean(&[-1.0, 4.0]), None);
+        assert_close(geometric_mean(&[1.0, 4.0]).unwrap(), 2.0, 1e-12);
+    }
+
+    #[test]
+    fn bootstrap_ci_is_seeded() {
+        let samples: Vec<f64> = (1..=50).map(|x| (x * x % 17) as f64).collect();
+        let config = SummaryConfig {
+            percentiles: Some(vec![]),
+            confidence_level: 0.9,
+            bootstrap_resamples: 500,
+        };
+        let a = summarize(samples.clone(), config.clone()).unwrap();
+        let b = summarize(samples, config).unwrap();
+        assert_eq!(a.mean_ci, b.mean_ci);
+        assert_eq!(a.mean_ci.level, 0.9);
+        assert!(a.mean_ci.lower < a.mean && a.mean < a.mean_ci.upper);
+        assert!(a.percentiles.is_empty());
+    }
+
+    #[test]
+    fn single_sample_has_degenerate_spread() {
+        let s = summarize(vec![5.0], SummaryConfig::default()).unwrap();
+        assert_eq!((s.stddev, s.mad), (0.0, 0.0));
+        assert_eq!((s.mean_ci.lower, s.mean_ci.upper), (5.0, 5.0));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let config = SummaryConfig::default;
+        assert!(summarize(vec![], config()).is_err());
+        assert!(summarize(vec![1.0, f64::NAN, 3.0], config()).is_err());
+        assert!(summarize(vec![1.0, f64::INFINITY], config()).is_err());
+        let bad_level = SummaryConfig {
+            confidence_level: 1.0,
+            ..config()
+        };
+        assert!(summarize(vec![1.0], bad_level).is_err());
+        let bad_percentile = SummaryConfig {
+            percentiles: Some(vec![101.0]),
+            ..config()
+        };
+        assert!(summarize(vec![1.0], bad_percentile).is_err());
+    }
+
+    #[test]
+    fn normal_cdf_matches_reference() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-7);
+        assert_close(normal_cdf(1.96), 0.975_002_104_851_78, 1e-7);
+        assert_close(normal_cdf(-2.5), 0.006_209_665_325_776_14, 1e-8);
+    }
+
+    #[test]
+    fn student_t_matches_reference() {
+        assert_close(
+            student_t_two_sided(2.0, 10.0),
+            0.073_388_034_770_740_4,
+            1e-12,
+        );
+        assert_close(
+            student_t_two_sided(-2.0, 10.0),
+            0.073_388_034_770_740_4,
+            1e-12,
+        );
+        assert_close(student_t_two_sided(0.0, 5.0), 1.0, 1e-12);
+    }
+
+    #[test]
+    fn incomplete_beta_matches_reference() {
+        assert_close(incomplete_beta(2.0, 3.0, 0.4), 0.5248, 1e-12);
+        assert_eq!(incomplete_beta(2.0, 3.0, 0.0), 0.0);
+        assert_eq!(incomplete_beta(2.0, 3.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn ln_gamma_matches_reference() {
+        assert_close(ln_gamma(0.5), 0.572_364_942_924_7, 1e-10);
+        assert_close(ln_gamma(10.0), 12.801_827_480_081_5, 1e-10);
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BenchResult, DeviceInfo, MetricsError, get_device_info};
+
+#[derive(Clone, Debug, Default, PartialEq, uniffi::Record, Serialize, Deserialize)]
+pub struct RunMetadata {
+    #[uniffi(default = None)]
+    pub app_version: Option<String>,
+    #[uniffi(default = None)]
+    pub git_sha: Option<String>,
+    /// Free-form description of the device, e.g. its model
+    #[uniffi(default = None)]
+    pub device: Option<String>,
+    /// Filled in with [`get_device_info`] when saved without one
+    #[uniffi(default = None)]
+    #[serde(default)]
+    pub device_info: Option<DeviceInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Milliseconds since the Unix epoch, like `Date.now()`
+    pub timestamp: f64,
+    pub metadata: RunMetadata,
+    pub result: BenchResult,
+}
+
+/// On-device history of benchmark results
+///
+/// Backed by an append-only JSON-lines file, one run per line.
+/// Lines that fail to parse, e.g. from an interrupted write, are skipped.
+#[derive(Debug, uniffi::Object)]
+pub struct ResultStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+#[uniffi::export]
+impl ResultStore {
+    /// Opens the store at `path`, creating it and its parents if needed
+    #[uniffi::constructor]
+    pub fn open(path: String) -> Result<Self, MetricsError> {
+        let path = PathBuf::from(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new().create(true).append(true).open(&path)?;
+
+        Ok(Self {
+            path,
+            lock: Mutex::new(()),
+        })
+    }
+
+    pub fn save(
+        &self,
+        result: BenchResult,
+        mut metadata: RunMetadata,
+    ) -> Result<RunRecord, MetricsError> {
+        metadata.device_info.get_or_insert_with(get_device_info);
+        let record = RunRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64()
+                * 1000.0,
+            metadata,
+            result,
+        };
+
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().unwrap();
+        let mut f = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        // an interrupted write may have left the last line without its newline
+        if f.metadata()?.len() > 0 {
+            let mut last = [0];
+            f.seek(SeekFrom::End(-1))?;
+            f.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                line.insert(0, '\n');
+            }
+        }
+        f.write_all(line.as_bytes())?;
+        f.flush()?;
+        Ok(record)
+    }
+
+    /// Runs of the benchmark `name`, oldest first
+    pub fn runs(&self, name: String) -> Result<Vec<RunRecord>, MetricsError> {
+        Ok(self
+            .read()?
+            .into_iter()
+            .filter(|r| r.result.name == name)
+            .collect())
+    }
+
+    pub fn latest(&self, name: String) -> Result<Option<RunRecord>, MetricsError> {
+        Ok(self.runs(name)?.pop())
+    }
+
+    /// Names of every stored benchmark, sorted
+    pub fn names(&self) -> Result<Vec<String>, MetricsError> {
+        let mut names: Vec<String> = self.read()?.into_iter().map(|r| r.result.name).collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+impl ResultStore {
+    fn read(&self) -> Result<Vec<RunRecord>, MetricsError> {
+        let _guard = self.lock.lock().unwrap();
+        let f = fs::File::open(&self.path)?;
+        // split on bytes, as a torn line need not be valid UTF-8
+        let lines = BufReader::new(f)
+            .split(b'\n')
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(lines
+            .iter()
+            .filter_map(|l| serde_json::from_slice(l).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        path::Path,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use super::*;
+
+    fn temp_store() -> (ResultStore, PathBuf) {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let path = env::temp_dir().join(format!(
+            "rnbench-store-{}-{}.jsonl",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_file(&path);
+        let store = ResultStore::open(path.to_string_lossy().into_owned()).unwrap();
+        (store, path)
+    }
+
+    fn result(name: &str, mean: f64) -> BenchResult {
+        BenchResult {
+            name: name.to_string(),
+            iterations: 1,
+            mean,
+            median: mean,
+            min: mean,
+            max: mean,
+            stddev: 0.0,
+            p95: mean,
+            p99: mean,
+            ops_per_sec: 1000.0 / mean,
+            samples: vec![mean],
+            throttled: false,
+        }
+    }
+
+    fn append(path: &Path, bytes: &[u8]) {
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(bytes)
+            .unwrap();
+    }
+
+    #[test]
+    fn saves_and_queries_runs() {
+        let (store, path) = temp_store();
+        store
+            .save(result("a", 1.0), RunMetadata::default())
+            .unwrap();
+        store
+            .save(result("b", 2.0), RunMetadata::default())
+            .unwrap();
+        store
+            .save(result("a", 3.0), RunMetadata::default())
+            .unwrap();
+
+        let runs = store.runs("a".to_string()).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(
+            store.latest("a".to_string()).unwrap().unwrap().result.mean,
+            3.0
+        );
+        assert_eq!(store.names().unwrap(), ["a", "b"]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let (store, path) = temp_store();
+        store
+            .save(result("a", 1.0), RunMetadata::default())
+            .unwrap();
+        append(&path, b"{\"timestamp\": 1, \xff\xfe\n");
+        append(&path, b"not json\n");
+        store
+            .save(result("a", 2.0), RunMetadata::default())
+            .unwrap();
+
+        let means: Vec<f64> = store
+            .runs("a".to_string())
+            .unwrap()
+            .iter()
+            .map(|r| r.result.mean)
+            .collect();
+        assert_eq!(means, [1.0, 2.0]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reads_records_without_device_info() {
+        let (store, path) = temp_store();
+        let mut record = serde_json::to_value(RunRecord {
+            timestamp: 1.0,
+            metadata: RunMetadata {
+                device: Some("Pixel 7".to_string()),
+                ..RunMetadata::default()
+            },
+            result: result("a", 1.0),
+        })
+        .unwrap();
+        record["metadata"]
+            .as_object_mut()
+            .unwrap()
+            .remove("device_info");
+        append(&path, format!("{}\n", record).as_bytes());
+
+        let runs = store.runs("a".to_string()).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].metadata.device.as_deref(), Some("Pixel 7"));
+        assert_eq!(runs[0].metadata.device_info, None);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn appends_after_torn_line() {
+        let (store, path) = temp_store();
+        store
+            .save(result("a", 1.0), RunMetadata::default())
+            .unwrap();
+        append(&path, b"{\"timestamp\": 1, \"meta");
+        store
+            .save(result("a", 2.0), RunMetadata::default())
+            .unwrap();
+
+        assert_eq!(store.runs("a".to_string()).unwrap().len(), 2);
+        fs::remove_file(path).unwrap();
+    }
+}
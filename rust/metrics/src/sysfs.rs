@@ -0,0 +1,86 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const DEFAULT_ROOT: &str = "/sys";
+
+/// Reads from a sysfs tree, `/sys` unless pointed at a fake one
+#[derive(Clone, Debug)]
+pub(crate) struct Sysfs {
+    root: PathBuf,
+}
+
+impl Sysfs {
+    pub(crate) fn new(root: Option<String>) -> Self {
+        Self {
+            root: root.unwrap_or_else(|| DEFAULT_ROOT.to_string()).into(),
+        }
+    }
+
+    /// Entries of `dir` whose name starts with `prefix`, sorted by name
+    pub(crate) fn entries(&self, dir: &str, prefix: &str) -> Vec<(String, PathBuf)> {
+        let Ok(rd) = fs::read_dir(self.root.join(dir)) else {
+            return vec![];
+        };
+
+        let mut entries: Vec<(String, PathBuf)> = rd
+            .filter_map(Result::ok)
+            .filter_map(|e| Some((e.file_name().into_string().ok()?, e.path())))
+            .filter(|(name, _)| name.starts_with(prefix))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    pub(crate) fn read<P: AsRef<Path>>(&self, path: P) -> Option<String> {
+        let s = fs::read_to_string(self.root.join(path)).ok()?;
+        Some(s.trim().to_string())
+    }
+
+    pub(crate) fn read_i64<P: AsRef<Path>>(&self, path: P) -> Option<i64> {
+        self.read(path)?.parse().ok()
+    }
+}
+
+/// Temporary sysfs tree for tests, removed on drop
+#[cfg(test)]
+pub(crate) struct FakeSysfs {
+    pub(crate) root: PathBuf,
+}
+
+#[cfg(test)]
+impl FakeSysfs {
+    pub(crate) fn new() -> Self {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "rnbench-sysfs-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    /// Writes `content` to `path`, creating its parents
+    pub(crate) fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("{}\n", content)).unwrap();
+        self
+    }
+
+    pub(crate) fn root(&self) -> Option<String> {
+        Some(self.root.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+impl Drop for FakeSysfs {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
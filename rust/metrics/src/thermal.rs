@@ -0,0 +1,170 @@
+use crate::sysfs::Sysfs;
+
+/// Some drivers report whole degrees instead of millidegrees
+const MILLIDEGREE_THRESHOLD: i64 = 1000;
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct ThermalZone {
+    /// Directory name, e.g. `thermal_zone0`
+    pub name: String,
+    /// Sensor type, e.g. `cpu-0-0-usr`
+    pub kind: String,
+    /// Degrees Celsius
+    pub temperature: f64,
+}
+
+/// Frequency limits of one core, in kHz
+#[derive(Copy, Clone, Debug, PartialEq, uniffi::Record)]
+pub struct CpuFrequencyCap {
+    pub cpu: u32,
+    pub current: Option<u64>,
+    /// Limit currently imposed by the governor or thermal framework
+    pub scaling_max: u64,
+    pub hardware_max: u64,
+    /// Whether `scaling_max` is below `hardware_max`
+    pub throttled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct ThermalStatus {
+    pub zones: Vec<ThermalZone>,
+    pub cpus: Vec<CpuFrequencyCap>,
+    /// Whether any core is throttled
+    pub throttled: bool,
+}
+
+/// Reads thermal zones and CPU frequency caps from sysfs
+///
+/// `sysfs_root` defaults to `/sys`. Empty where sysfs is not available.
+#[uniffi::export]
+pub fn get_thermal_status(sysfs_root: Option<String>) -> ThermalStatus {
+    let sysfs = Sysfs::new(sysfs_root);
+
+    let zones = sysfs
+        .entries("class/thermal", "thermal_zone")
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let raw = sysfs.read_i64(path.join("temp"))?;
+            let temperature = if raw.abs() >= MILLIDEGREE_THRESHOLD {
+                raw as f64 / 1000.0
+            } else {
+                raw as f64
+            };
+            Some(ThermalZone {
+                kind: sysfs.read(path.join("type")).unwrap_or_default(),
+                name,
+                temperature,
+            })
+        })
+        .collect();
+
+    let mut cpus: Vec<CpuFrequencyCap> = sysfs
+        .entries("devices/system/cpu", "cpu")
+        .into_iter()
+        .filter_map(|(name, path)| {
+            let cpu = name.strip_prefix("cpu")?.parse().ok()?;
+            let freq = path.join("cpufreq");
+            let scaling_max = sysfs.read_i64(freq.join("scaling_max_freq"))? as u64;
+            let hardware_max = sysfs.read_i64(freq.join("cpuinfo_max_freq"))? as u64;
+            Some(CpuFrequencyCap {
+                cpu,
+                current: sysfs
+                    .read_i64(freq.join("scaling_cur_freq"))
+                    .map(|f| f as u64),
+                scaling_max,
+                hardware_max,
+                throttled: scaling_max < hardware_max,
+            })
+        })
+        .collect();
+    // `cpu10` sorts before `cpu2` by name
+    cpus.sort_by_key(|c| c.cpu);
+
+    ThermalStatus {
+        throttled: cpus.iter().any(|c| c.throttled),
+        zones,
+        cpus,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::FakeSysfs;
+
+    fn cpu(fake: &FakeSysfs, n: u32, scaling_max: u64, hardware_max: u64) {
+        let dir = format!("devices/system/cpu/cpu{}/cpufreq", n);
+        fake.write(
+            &format!("{}/scaling_max_freq", dir),
+            &scaling_max.to_string(),
+        )
+        .write(
+            &format!("{}/cpuinfo_max_freq", dir),
+            &hardware_max.to_string(),
+        )
+        .write(&format!("{}/scaling_cur_freq", dir), "1200000");
+    }
+
+    #[test]
+    fn reads_millidegrees_and_degrees() {
+        let fake = FakeSysfs::new();
+        fake.write("class/thermal/thermal_zone0/temp", "45300")
+            .write("class/thermal/thermal_zone0/type", "cpu-0-0-usr")
+            .write("class/thermal/thermal_zone1/temp", "38")
+            .write("class/thermal/thermal_zone1/type", "battery")
+            .write("class/thermal/thermal_zone2/temp", "-5000")
+            .write("class/thermal/thermal_zone2/type", "ambient")
+            .write("class/thermal/thermal_zone3/type", "no-temp");
+
+        let zones = get_thermal_status(fake.root()).zones;
+        let temps: Vec<(&str, f64)> = zones
+            .iter()
+            .map(|z| (z.kind.as_str(), z.temperature))
+            .collect();
+        assert_eq!(
+            temps,
+            [("cpu-0-0-usr", 45.3), ("battery", 38.0), ("ambient", -5.0)]
+        );
+        assert_eq!(zones[0].name, "thermal_zone0");
+    }
+
+    #[test]
+    fn orders_cpus_numerically() {
+        let fake = FakeSysfs::new();
+        for n in [10, 2, 0, 1] {
+            cpu(&fake, n, 2_000_000, 2_000_000);
+        }
+        fake.write("devices/system/cpu/cpufreq/policy0/scaling_max_freq", "1")
+            .write("devices/system/cpu/cpuidle/current_driver", "none");
+
+        let cpus: Vec<u32> = get_thermal_status(fake.root())
+            .cpus
+            .iter()
+            .map(|c| c.cpu)
+            .collect();
+        assert_eq!(cpus, [0, 1, 2, 10]);
+    }
+
+    #[test]
+    fn flags_capped_cpus_as_throttled() {
+        let fake = FakeSysfs::new();
+        cpu(&fake, 0, 2_000_000, 2_000_000);
+        let status = get_thermal_status(fake.root());
+        assert!(!status.throttled);
+        assert_eq!(status.cpus[0].current, Some(1_200_000));
+
+        cpu(&fake, 1, 1_500_000, 2_400_000);
+        let status = get_thermal_status(fake.root());
+        assert!(status.throttled);
+        assert!(!status.cpus[0].throttled);
+        assert!(status.cpus[1].throttled);
+    }
+
+    #[test]
+    fn empty_without_sysfs() {
+        let fake = FakeSysfs::new();
+        let status = get_thermal_status(fake.root());
+        assert!(status.zones.is_empty() && status.cpus.is_empty());
+        assert!(!status.throttled);
+    }
+}
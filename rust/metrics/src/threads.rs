@@ -0,0 +1,178 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{MetricsError, blocking};
+
+/// CPU time a thread of the app process spent during an interval
+#[derive(Clone, Debug, PartialEq, uniffi::Record, serde::Serialize)]
+pub struct ThreadCpu {
+    pub tid: u32,
+    /// e.g. `mqt_js` for the JS thread or `main` for the UI thread on Android
+    pub name: String,
+    /// User and system CPU time in milliseconds
+    pub user: f64,
+    pub system: f64,
+    /// Percent of one core
+    pub usage: f32,
+}
+
+/// CPU time of every thread of the current process over `interval_ms`,
+/// busiest first
+///
+/// Threads that exit during the interval are left out. Empty where
+/// `/proc` is unavailable.
+#[uniffi::export]
+pub fn get_thread_cpu(interval_ms: u32) -> Result<Vec<ThreadCpu>, MetricsError> {
+    if interval_ms == 0 {
+        return Err(MetricsError::InvalidArgument {
+            reason: "interval must be positive".to_string(),
+        });
+    }
+
+    let start = Instant::now();
+    let before = thread_times();
+    thread::sleep(Duration::from_millis(interval_ms as u64));
+    let after = thread_times();
+    let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+
+    let mut threads: Vec<ThreadCpu> = after
+        .into_iter()
+        .map(|t| {
+            // threads started during the interval spent all their time in it
+            let (user, system) = before
+                .iter()
+                .find(|b| b.tid == t.tid)
+                .map_or((0.0, 0.0), |b| (b.user, b.system));
+            let user = (t.user - user).max(0.0);
+            let system = (t.system - system).max(0.0);
+
+            ThreadCpu {
+                tid: t.tid,
+                name: t.name,
+                user,
+                system,
+                usage: ((user + system) / elapsed * 100.0) as f32,
+            }
+        })
+        .collect();
+    threads.sort_by(|a, b| b.usage.total_cmp(&a.usage).then(a.tid.cmp(&b.tid)));
+    Ok(threads)
+}
+
+/// Non-blocking [`get_thread_cpu`], measured on a background thread
+#[uniffi::export]
+pub async fn get_thread_cpu_async(interval_ms: u32) -> Result<Vec<ThreadCpu>, MetricsError> {
+    blocking::spawn(move || get_thread_cpu(interval_ms)).await
+}
+
+/// Total CPU time of a thread in milliseconds
+#[derive(Debug)]
+struct ThreadTimes {
+    tid: u32,
+    name: String,
+    user: f64,
+    system: f64,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn thread_times() -> Vec<ThreadTimes> {
+    let Ok(entries) = std::fs::read_dir("/proc/self/task") else {
+        return vec![];
+    };
+    let ms_per_tick = 1000.0 / crate::procfs::clock_ticks();
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let tid = entry.file_name().to_str()?.parse().ok()?;
+            // the thread may have exited since the directory was listed
+            let stat = crate::procfs::read_stat(entry.path().join("stat")).ok()?;
+            Some(ThreadTimes {
+                tid,
+                name: stat.comm,
+                user: stat.utime as f64 * ms_per_tick,
+                system: stat.stime as f64 * ms_per_tick,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn thread_times() -> Vec<ThreadTimes> {
+    vec![]
+}
+
+/// Busy thread for CPU accounting tests, stopped on drop
+///
+/// Only one runs at a time so tests do not compete for the CPU.
+#[cfg(test)]
+pub(crate) struct Spinner {
+    running: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+    _turn: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl Spinner {
+    pub(crate) fn start(name: &str) -> Self {
+        use std::sync::{
+            Arc, Mutex, PoisonError,
+            atomic::{AtomicBool, Ordering},
+        };
+
+        static TURN: Mutex<()> = Mutex::new(());
+        let turn = TURN.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let running = Arc::new(AtomicBool::new(true));
+        let spinning = running.clone();
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                while spinning.load(Ordering::Relaxed) {
+                    std::hint::spin_loop();
+                }
+            })
+            .unwrap();
+        Self {
+            running,
+            handle: Some(handle),
+            _turn: turn,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.running
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn busiest_thread_comes_first() {
+        let _spinner = Spinner::start("rnbench-spin");
+        let threads = get_thread_cpu(500).unwrap();
+
+        assert_eq!(threads[0].name, "rnbench-spin");
+        assert!(threads[0].usage > 20.0, "usage {}", threads[0].usage);
+        assert!(threads[0].user > 0.0);
+        assert!(threads.windows(2).all(|w| w[0].usage >= w[1].usage));
+        assert!(threads.iter().any(|t| t.tid == std::process::id()));
+    }
+
+    #[test]
+    fn rejects_zero_interval() {
+        assert!(get_thread_cpu(0).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use std::sync::Mutex;
+
+use crate::{MetricsError, clock};
+
+static TIMINGS: Mutex<Timings> = Mutex::new(Timings {
+    marks: Vec::new(),
+    measures: Vec::new(),
+});
+
+#[derive(Debug)]
+struct Timings {
+    marks: Vec<Mark>,
+    measures: Vec<Measure>,
+}
+
+/// A named point in time, on the same clock as [`crate::Sample`]
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Mark {
+    pub name: String,
+    /// Milliseconds on the crate's monotonic clock
+    pub timestamp: f64,
+}
+
+/// A named span between two marks
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct Measure {
+    pub name: String,
+    /// Milliseconds on the crate's monotonic clock
+    pub start: f64,
+    pub duration: f64,
+}
+
+#[uniffi::export]
+pub fn mark(name: String) -> Mark {
+    let m = Mark {
+        name,
+        timestamp: clock::now_ms(),
+    };
+    TIMINGS.lock().unwrap().marks.push(m.clone());
+    m
+}
+
+/// Records a measure between the latest marks named `start_mark` and `end_mark`
+///
+/// Like `performance.measure`, a missing start is the clock's origin
+/// and a missing end is now.
+#[uniffi::export]
+pub fn measure(
+    name: String,
+    start_mark: Option<String>,
+    end_mark: Option<String>,
+) -> Result<Measure, MetricsError> {
+    let end_now = clock::now_ms();
+    let mut timings = TIMINGS.lock().unwrap();
+
+    let find = |mark: Option<String>, default: f64| match mark {
+        None => Ok(default),
+        Some(name) => timings
+            .marks
+            .iter()
+            .rfind(|m| m.name == name)
+            .map(|m| m.timestamp)
+            .ok_or(MetricsError::MarkNotFound { name }),
+    };
+    let start = find(start_mark, 0.0)?;
+    let end = find(end_mark, end_now)?;
+
+    let m = Measure {
+        name,
+        start,
+        duration: end - start,
+    };
+    timings.measures.push(m.clone());
+    Ok(m)
+}
+
+/// Every mark in the order recorded
+#[uniffi::export]
+pub fn get_marks() -> Vec<Mark> {
+    TIMINGS.lock().unwrap().marks.clone()
+}
+
+/// Every measure in the order recorded
+#[uniffi::export]
+pub fn get_measures() -> Vec<Measure> {
+    TIMINGS.lock().unwrap().measures.clone()
+}
+
+/// Clears marks named `name`, or all of them if not given
+#[uniffi::export]
+pub fn clear_marks(name: Option<String>) {
+    let mut timings = TIMINGS.lock().unwrap();
+    match name {
+        Some(name) => timings.marks.retain(|m| m.name != name),
+        None => timings.marks.clear(),
+    }
+}
+
+/// Clears measures named `name`, or all of them if not given
+#[uniffi::export]
+pub fn clear_measures(name: Option<String>) {
+    let mut timings = TIMINGS.lock().unwrap();
+    match name {
+        Some(name) => timings.measures.retain(|m| m.name != name),
+        None => timings.measures.clear(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+
+    // timings are global, so every test uses its own names
+
+    #[test]
+    fn latest_mark_wins() {
+        let first = mark("latest.start".to_string());
+        thread::sleep(Duration::from_millis(5));
+        let second = mark("latest.start".to_string());
+        let end = mark("latest.end".to_string());
+        assert!(second.timestamp > first.timestamp);
+
+        let m = measure(
+            "latest".to_string(),
+            Some("latest.start".to_string()),
+            Some("latest.end".to_string()),
+        )
+        .unwrap();
+        assert_eq!(m.start, second.timestamp);
+        assert_eq!(m.duration, end.timestamp - second.timestamp);
+        assert!(get_measures().contains(&m));
+    }
+
+    #[test]
+    fn missing_start_is_clock_origin() {
+        let end = mark("origin.end".to_string());
+        let m = measure("origin".to_string(), None, Some("origin.end".to_string())).unwrap();
+        assert_eq!(m.start, 0.0);
+        assert_eq!(m.duration, end.timestamp);
+    }
+
+    #[test]
+    fn missing_end_is_now() {
+        let start = mark("now.start".to_string());
+        let before = clock::now_ms();
+        let m = measure("now".to_string(), Some("now.start".to_string()), None).unwrap();
+        let after = clock::now_ms();
+        assert_eq!(m.start, start.timestamp);
+        assert!((before..=after).contains(&(m.start + m.duration)));
+    }
+
+    #[test]
+    fn unknown_mark_is_an_error() {
+        let err = measure(
+            "unknown".to_string(),
+            Some("unknown.mark".to_string()),
+            None,
+        );
+        assert!(matches!(
+            err,
+            Err(MetricsError::MarkNotFound { name }) if name == "unknown.mark"
+        ));
+        assert!(!get_measures().iter().any(|m| m.name == "unknown"));
+    }
+
+    #[test]
+    fn clears_by_name() {
+        mark("clear.a".to_string());
+        mark("clear.b".to_string());
+        measure("clear.m".to_string(), None, None).unwrap();
+
+        clear_marks(Some("clear.a".to_string()));
+        clear_measures(Some("clear.m".to_string()));
+        let marks = get_marks();
+        assert!(!marks.iter().any(|m| m.name == "clear.a"));
+        assert!(marks.iter().any(|m| m.name == "clear.b"));
+        assert!(!get_measures().iter().any(|m| m.name == "clear.m"));
+        assert!(measure("clear.m".to_string(), Some("clear.a".to_string()), None).is_err());
+    }
+}